@@ -1,9 +1,11 @@
-use crate::attr_arg::AttrArg;
+use crate::attr_arg::{AttrArg, Lit, Meta, MetaList, parse_meta};
+use crate::cfg::Cfg;
 use crate::formatter::Formatter;
+use crate::parse::{ParseError, Parser};
 use std::fmt::{self, Write};
 
 /// An inner attribute or outer attribute.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Attr {
   /// attribute name
   pub name: String,
@@ -33,12 +35,192 @@ impl Attr {
     self
   }
 
-  /// Set attribute argument to a delimited token tree.
+  /// Set attribute argument to a delimited token tree, parsed into a meta
+  /// list if possible.
   pub fn arg_delimited(&mut self, delim: impl Into<String>) -> &mut Self {
-    self.arg = Some(AttrArg::Delimited(delim.into()));
+    self.arg = Some(AttrArg::delimited(delim));
     self
   }
 
+  /// Returns the argument as a mutable meta list, e.g. to build
+  /// `#[serde(rename = "a", default)]`. An attribute without an argument
+  /// gets an empty list.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the argument is an expression or a token tree that is not
+  /// a meta list.
+  pub fn list(&mut self) -> &mut MetaList {
+    if self.arg.is_none() {
+      self.arg = Some(AttrArg::List(MetaList::new()));
+    }
+
+    match &mut self.arg {
+      Some(AttrArg::List(list)) => list,
+      _ => panic!("the argument of `{}` is not a meta list", self.name),
+    }
+  }
+
+  /// Returns the argument if it is a meta list.
+  pub fn meta_list(&self) -> Option<&MetaList> {
+    match &self.arg {
+      Some(AttrArg::List(list)) => Some(list),
+      _ => None,
+    }
+  }
+
+  /// Merge the meta list of another attribute with the same name into this
+  /// one, e.g. combining `#[serde(default)]` and `#[serde(rename = "a")]`.
+  /// See `MetaList::merge`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the names differ or either argument is not a meta list.
+  pub fn merge(&mut self, other: Attr) -> &mut Self {
+    assert_eq!(self.name, other.name, "cannot merge attributes with different names");
+
+    match other.arg {
+      Some(AttrArg::List(list)) => {
+        self.list().merge(list);
+      }
+      None => {}
+      Some(_) => panic!("the argument of `{}` is not a meta list", other.name),
+    }
+
+    self
+  }
+
+  /// Create a `#[cfg(pred)]` attribute.
+  pub fn cfg(pred: Cfg) -> Self {
+    let mut attr = Attr::new("cfg");
+    attr.list().push(pred.to_meta());
+    attr
+  }
+
+  /// Create a `#[cfg_attr(pred, attr)]` attribute, applying `attr` only if
+  /// the predicate holds.
+  pub fn cfg_attr(pred: Cfg, attr: Attr) -> Self {
+    let meta = match attr.arg {
+      None => Some(Meta::Path(attr.name.clone())),
+      Some(AttrArg::List(ref list)) => Some(Meta::List(attr.name.clone(), list.clone())),
+      Some(AttrArg::Expr(ref expr)) => {
+        Lit::parse(expr).map(|lit| Meta::NameValue(attr.name.clone(), lit))
+      }
+      Some(AttrArg::Delimited(_)) => None,
+    };
+
+    let mut cfg_attr = Attr::new("cfg_attr");
+
+    match meta {
+      Some(meta) => {
+        cfg_attr.list().push(pred.to_meta()).push(meta);
+      }
+      None => {
+        let mut inner = String::new();
+        attr.fmt_meta(&mut inner).unwrap();
+        cfg_attr.arg = Some(AttrArg::Delimited(format!("{}, {}", pred, inner)));
+      }
+    }
+
+    cfg_attr
+  }
+
+  /// Returns the predicate of a `#[cfg]` or `#[cfg_attr]` attribute.
+  pub fn cfg_predicate(&self) -> Option<Cfg> {
+    let meta = match &self.arg {
+      Some(AttrArg::List(list)) if self.name == "cfg" && list.items().len() == 1 => {
+        &list.items()[0]
+      }
+      Some(AttrArg::List(list)) if self.name == "cfg_attr" && list.items().len() >= 2 => {
+        &list.items()[0]
+      }
+      Some(AttrArg::Delimited(src)) if self.name == "cfg_attr" => {
+        let mut parser = Parser::new(src);
+        let meta = parse_meta(&mut parser).ok()?;
+
+        if !parser.eat(",") {
+          return None;
+        }
+
+        return Cfg::from_meta(&meta);
+      }
+      _ => return None,
+    };
+
+    Cfg::from_meta(meta)
+  }
+
+  /// Returns the paths the attribute refers to: its name, the traits of a
+  /// `derive` and the attributes applied by a `cfg_attr`.
+  pub(crate) fn paths(&self) -> Vec<&str> {
+    let mut paths = vec![&self.name[..]];
+
+    if let Some(list) = self.meta_list() {
+      match &self.name[..] {
+        "derive" => paths.extend(list.items().iter().filter_map(derived)),
+        "cfg_attr" => {
+          for meta in list.items().iter().skip(1) {
+            paths.extend(meta.name());
+
+            if let Meta::List(name, list) = meta {
+              if name == "derive" {
+                paths.extend(list.items().iter().filter_map(derived));
+              }
+            }
+          }
+        }
+        _ => {}
+      }
+    }
+
+    paths
+  }
+
+  /// Parse an attribute, e.g. `#[serde(default)]` or `doc = "..."`. The
+  /// surrounding `#[...]` is optional.
+  pub fn parse(src: &str) -> Result<Self, ParseError> {
+    let src = src.trim();
+    let body = match src.strip_prefix("#[") {
+      Some(body) => match body.strip_suffix(']') {
+        Some(body) => body,
+        None => return Err(ParseError::new("expected `]`", src.len())),
+      },
+      None => src,
+    };
+
+    let mut parser = Parser::new(body);
+    let mut name = String::new();
+
+    loop {
+      match parser.ident() {
+        Some(seg) => name.push_str(&seg),
+        None => return parser.error("expected identifier"),
+      }
+
+      if !parser.eat("::") {
+        break;
+      }
+
+      name.push_str("::");
+    }
+
+    let arg = if parser.eat("(") {
+      let arg = parser.raw_until_delim(')')?;
+      parser.expect(")")?;
+      Some(AttrArg::delimited(arg.trim()))
+    } else if parser.eat("=") {
+      match parser.take_rest().trim() {
+        "" => return parser.error("expected an expression"),
+        expr => Some(AttrArg::Expr(expr.to_string())),
+      }
+    } else {
+      None
+    };
+
+    parser.finish()?;
+    Ok(Attr { name, arg })
+  }
+
   /// Format the attribute.
   pub fn fmt(&self, fmt: &mut Formatter, inner: bool) -> fmt::Result {
     let prefix = if inner {
@@ -47,14 +229,104 @@ impl Attr {
       "#"
     };
 
-    write!(fmt, "{}[{}", prefix, self.name)?;
+    fmt.record_attr(self);
+
+    write!(fmt, "{}[", prefix)?;
+    self.fmt_meta(fmt)?;
+    writeln!(fmt, "]")
+  }
+
+  /// Format the outer attribute without a trailing newline, e.g. before a
+  /// parameter.
+  pub(crate) fn fmt_inline(&self, fmt: &mut Formatter) -> fmt::Result {
+    fmt.record_attr(self);
+
+    write!(fmt, "#[")?;
+    self.fmt_meta(fmt)?;
+    write!(fmt, "]")
+  }
+
+  fn fmt_meta(&self, fmt: &mut impl Write) -> fmt::Result {
+    write!(fmt, "{}", self.name)?;
 
     match &self.arg {
-      Some(AttrArg::Delimited(delim)) => write!(fmt, "({})", delim)?,
-      Some(AttrArg::Expr(expr)) => write!(fmt, " = {}", expr)?,
-      None => {}
+      Some(AttrArg::Delimited(delim)) => write!(fmt, "({})", delim),
+      Some(AttrArg::Expr(expr)) => write!(fmt, " = {}", expr),
+      Some(AttrArg::List(list)) => write!(fmt, "({})", list),
+      None => Ok(()),
     }
+  }
+}
 
-    writeln!(fmt, "]")
+/// Writes the outer attribute, e.g. `#[serde(default)]`.
+impl fmt::Display for Attr {
+  fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    write!(fmt, "#[")?;
+    self.fmt_meta(fmt)?;
+    write!(fmt, "]")
+  }
+}
+
+/// Parses the string, panicking if it is not a valid attribute.
+impl<'a> From<&'a str> for Attr {
+  fn from(src: &'a str) -> Self {
+    match Attr::parse(src) {
+      Ok(attr) => attr,
+      Err(err) => panic!("invalid attribute `{}`: {}", src, err),
+    }
+  }
+}
+
+impl From<String> for Attr {
+  fn from(src: String) -> Self {
+    Attr::from(&src[..])
+  }
+}
+
+impl<'a> From<&'a String> for Attr {
+  fn from(src: &'a String) -> Self {
+    Attr::from(&src[..])
+  }
+}
+
+/// Returns the trait named by an item of a `derive` list.
+fn derived(meta: &Meta) -> Option<&str> {
+  match meta {
+    Meta::Path(path) => Some(path),
+    _ => None,
+  }
+}
+
+/// Pushes `derive(name)`, merging it into an existing `derive` attribute.
+pub(crate) fn push_derive(attrs: &mut Vec<Attr>, name: &str) {
+  let mut derive = Attr::new("derive");
+  derive.arg_delimited(name);
+  merge_attr(attrs, derive);
+}
+
+/// Merges the attribute into the first attribute with the same name and a
+/// meta list argument, pushing it if there is none.
+pub(crate) fn merge_attr(attrs: &mut Vec<Attr>, attr: Attr) {
+  let mergeable = matches!(attr.arg, Some(AttrArg::List(_)));
+  let existing = attrs.iter_mut()
+    .find(|a| a.name == attr.name && a.meta_list().is_some());
+
+  match existing {
+    Some(existing) if mergeable => {
+      existing.merge(attr);
+    }
+    _ => attrs.push(attr),
+  }
+}
+
+/// Sets the argument of the attribute with the given name, pushing the
+/// attribute if it does not exist.
+pub(crate) fn set_attr(attrs: &mut Vec<Attr>, name: &str, arg: AttrArg) {
+  match attrs.iter_mut().find(|attr| attr.name == name) {
+    Some(attr) => attr.arg = Some(arg),
+    None => attrs.push(Attr {
+      name: name.to_string(),
+      arg: Some(arg),
+    }),
   }
 }
\ No newline at end of file
@@ -1,9 +1,401 @@
+use crate::parse::{ParseError, Parser};
+use std::fmt;
 
 /// The argument of an attribute.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AttrArg {
-  /// delimited token tree, e.g. `#[name(ARG)]`
+  /// delimited token tree that is not a meta list, e.g. `#[name(ARG)]`
   Delimited(String),
   /// literal expression, e.g. `#[name = EXPR]`
   Expr(String),
-}
\ No newline at end of file
+  /// structured meta list, e.g. `#[serde(rename = "a", default)]`
+  List(MetaList),
+}
+
+impl AttrArg {
+  /// Returns a delimited argument, parsed into a meta list if possible.
+  /// The text is written as given either way.
+  pub fn delimited(src: impl Into<String>) -> Self {
+    let src = src.into();
+
+    match MetaList::parse(&src) {
+      Ok(list) => AttrArg::List(list),
+      Err(_) => AttrArg::Delimited(src),
+    }
+  }
+}
+
+/// A comma-separated list of meta items, e.g. the `rename = "a", default`
+/// in `#[serde(rename = "a", default)]`.
+///
+/// ```
+/// use codegen::{Attr, Lit};
+///
+/// let mut attr = Attr::new("serde");
+/// attr.list()
+///   .path("default")
+///   .new_list("rename")
+///   .name_value("serialize", "a\"b");
+///
+/// assert_eq!(attr.to_string(), r#"#[serde(default, rename(serialize = "a\"b"))]"#);
+///
+/// let rename = attr.meta_list().unwrap().get_list("rename").unwrap();
+/// assert_eq!(rename.value("serialize"), Some(&Lit::from("a\"b")));
+/// ```
+///
+/// A parsed list is written as it was given until it is changed.
+#[derive(Debug, Clone, Default)]
+pub struct MetaList {
+  items: Vec<Meta>,
+  /// source text the list was parsed from
+  raw: Option<String>,
+}
+
+/// An item of a meta list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Meta {
+  /// path, e.g. `default` or `clippy::all`
+  Path(String),
+  /// `key = literal`, e.g. `rename = "a"`
+  NameValue(String, Lit),
+  /// nested list, e.g. `rename(serialize = "a")`
+  List(String, MetaList),
+  /// literal, e.g. the `8` in `align(8)`
+  Lit(Lit),
+}
+
+/// A literal in a meta list. Strings are escaped when formatted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Lit {
+  /// string literal
+  Str(String),
+  /// unsuffixed integer literal
+  Int(i128),
+  /// `true` or `false`
+  Bool(bool),
+  /// any other literal, written verbatim, e.g. `1.5` or `'a'`
+  Verbatim(String),
+}
+
+impl MetaList {
+  /// Create an empty meta list.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Parse a meta list, e.g. `rename = "a", default`.
+  pub fn parse(src: &str) -> Result<Self, ParseError> {
+    let mut parser = Parser::new(src);
+    let mut items = vec![];
+
+    while parser.peek().is_some() {
+      items.push(parse_meta(&mut parser)?);
+
+      if !parser.eat(",") {
+        break;
+      }
+    }
+
+    parser.finish()?;
+    Ok(MetaList {
+      items,
+      raw: Some(src.to_string()),
+    })
+  }
+
+  /// Push a meta item.
+  pub fn push(&mut self, meta: Meta) -> &mut Self {
+    self.raw = None;
+    self.items.push(meta);
+    self
+  }
+
+  /// Push a path, e.g. `default`.
+  pub fn path(&mut self, path: impl Into<String>) -> &mut Self {
+    self.push(Meta::Path(path.into()))
+  }
+
+  /// Push a `key = literal` pair, e.g. `rename = "a"`.
+  pub fn name_value(&mut self, key: impl Into<String>, value: impl Into<Lit>) -> &mut Self {
+    self.push(Meta::NameValue(key.into(), value.into()))
+  }
+
+  /// Push a literal, e.g. the `8` in `align(8)`.
+  pub fn lit(&mut self, lit: impl Into<Lit>) -> &mut Self {
+    self.push(Meta::Lit(lit.into()))
+  }
+
+  /// Push a nested list, returning a mutable reference to it.
+  pub fn new_list(&mut self, name: impl Into<String>) -> &mut MetaList {
+    self.push(Meta::List(name.into(), MetaList::new()));
+
+    match self.items.last_mut() {
+      Some(Meta::List(_, list)) => list,
+      _ => unreachable!(),
+    }
+  }
+
+  /// Returns the items of the list.
+  pub fn items(&self) -> &[Meta] {
+    &self.items
+  }
+
+  /// Returns true if the list has no items.
+  pub fn is_empty(&self) -> bool {
+    self.items.is_empty()
+  }
+
+  /// Returns the first item with the given name.
+  pub fn get(&self, name: &str) -> Option<&Meta> {
+    self.items.iter().find(|meta| meta.name() == Some(name))
+  }
+
+  /// Returns true if the list has an item with the given name.
+  pub fn contains(&self, name: &str) -> bool {
+    self.get(name).is_some()
+  }
+
+  /// Returns the value of the `key = literal` pair with the given key.
+  pub fn value(&self, key: &str) -> Option<&Lit> {
+    self.items.iter().find_map(|meta| match meta {
+      Meta::NameValue(name, lit) if name == key => Some(lit),
+      _ => None,
+    })
+  }
+
+  /// Returns the nested list with the given name.
+  pub fn get_list(&self, name: &str) -> Option<&MetaList> {
+    self.items.iter().find_map(|meta| match meta {
+      Meta::List(n, list) if n == name => Some(list),
+      _ => None,
+    })
+  }
+
+  /// Merge another list into this one.
+  ///
+  /// Paths and literals already in the list are skipped, values of
+  /// existing keys are replaced, and nested lists with the same name are
+  /// merged recursively.
+  pub fn merge(&mut self, other: MetaList) -> &mut Self {
+    self.raw = None;
+
+    for meta in other.items {
+      match meta {
+        Meta::NameValue(key, value) => {
+          let existing = self.items.iter_mut().find_map(|meta| match meta {
+            Meta::NameValue(name, lit) if *name == key => Some(lit),
+            _ => None,
+          });
+
+          match existing {
+            Some(lit) => *lit = value,
+            None => self.items.push(Meta::NameValue(key, value)),
+          }
+        }
+        Meta::List(name, list) => {
+          let existing = self.items.iter_mut().find_map(|meta| match meta {
+            Meta::List(n, existing) if *n == name => Some(existing),
+            _ => None,
+          });
+
+          match existing {
+            Some(existing) => {
+              existing.merge(list);
+            }
+            None => self.items.push(Meta::List(name, list)),
+          }
+        }
+        meta => {
+          if !self.items.contains(&meta) {
+            self.items.push(meta);
+          }
+        }
+      }
+    }
+
+    self
+  }
+}
+
+impl Meta {
+  /// Returns the path, key or list name of the item, or `None` for a
+  /// literal.
+  pub fn name(&self) -> Option<&str> {
+    match self {
+      Meta::Path(name) | Meta::NameValue(name, _) | Meta::List(name, _) => Some(name),
+      Meta::Lit(_) => None,
+    }
+  }
+}
+
+impl Lit {
+  /// Parse a literal, e.g. `"a"` or `8`.
+  pub(crate) fn parse(src: &str) -> Option<Self> {
+    let mut parser = Parser::new(src);
+    let lit = parse_lit(&mut parser).ok()??;
+
+    parser.finish().ok()?;
+    Some(lit)
+  }
+
+  /// Returns the contents of a string literal.
+  pub fn as_str(&self) -> Option<&str> {
+    match self {
+      Lit::Str(s) => Some(s),
+      _ => None,
+    }
+  }
+}
+
+/// Lists are equal if their items are, however they are written.
+impl PartialEq for MetaList {
+  fn eq(&self, other: &Self) -> bool {
+    self.items == other.items
+  }
+}
+
+impl Eq for MetaList {}
+
+impl fmt::Display for MetaList {
+  fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    if let Some(raw) = &self.raw {
+      return write!(fmt, "{}", raw);
+    }
+
+    for (i, meta) in self.items.iter().enumerate() {
+      if i != 0 {
+        write!(fmt, ", ")?;
+      }
+
+      write!(fmt, "{}", meta)?;
+    }
+
+    Ok(())
+  }
+}
+
+impl fmt::Display for Meta {
+  fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Meta::Path(path) => write!(fmt, "{}", path),
+      Meta::NameValue(key, value) => write!(fmt, "{} = {}", key, value),
+      Meta::List(name, list) => write!(fmt, "{}({})", name, list),
+      Meta::Lit(lit) => write!(fmt, "{}", lit),
+    }
+  }
+}
+
+impl fmt::Display for Lit {
+  fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Lit::Str(s) => write!(fmt, "{:?}", s),
+      Lit::Int(n) => write!(fmt, "{}", n),
+      Lit::Bool(b) => write!(fmt, "{}", b),
+      Lit::Verbatim(lit) => write!(fmt, "{}", lit),
+    }
+  }
+}
+
+impl<'a> From<&'a str> for Lit {
+  fn from(s: &'a str) -> Self {
+    Lit::Str(s.to_string())
+  }
+}
+
+impl From<String> for Lit {
+  fn from(s: String) -> Self {
+    Lit::Str(s)
+  }
+}
+
+impl<'a> From<&'a String> for Lit {
+  fn from(s: &'a String) -> Self {
+    Lit::Str(s.clone())
+  }
+}
+
+impl From<bool> for Lit {
+  fn from(b: bool) -> Self {
+    Lit::Bool(b)
+  }
+}
+
+macro_rules! impl_from_int {
+  ($($ty:ty),*) => {
+    $(
+      impl From<$ty> for Lit {
+        fn from(n: $ty) -> Self {
+          Lit::Int(n as i128)
+        }
+      }
+    )*
+  };
+}
+
+impl_from_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+
+pub(crate) fn parse_meta(parser: &mut Parser) -> Result<Meta, ParseError> {
+  if let Some(lit) = parse_lit(parser)? {
+    return Ok(Meta::Lit(lit));
+  }
+
+  let mut path = String::new();
+
+  if parser.eat("::") {
+    path.push_str("::");
+  }
+
+  loop {
+    match parser.ident() {
+      Some(seg) => path.push_str(&seg),
+      None => return parser.error("expected a path or literal"),
+    }
+
+    if !parser.eat("::") {
+      break;
+    }
+
+    path.push_str("::");
+  }
+
+  if parser.eat("(") {
+    let items = parser.parse_list(")", parse_meta)?.0;
+    Ok(Meta::List(path, MetaList { items, raw: None }))
+  } else if parser.eat("=") {
+    match parse_lit(parser)? {
+      Some(lit) => Ok(Meta::NameValue(path, lit)),
+      None => parser.error("expected a literal"),
+    }
+  } else {
+    Ok(Meta::Path(path))
+  }
+}
+
+fn parse_lit(parser: &mut Parser) -> Result<Option<Lit>, ParseError> {
+  if let Some(s) = parser.str_lit()? {
+    return Ok(Some(Lit::Str(s)));
+  }
+
+  if parser.eat_keyword("true") {
+    return Ok(Some(Lit::Bool(true)));
+  }
+
+  if parser.eat_keyword("false") {
+    return Ok(Some(Lit::Bool(false)));
+  }
+
+  let neg = parser.eat("-");
+
+  match parser.number() {
+    Some(number) => {
+      let sign = if neg { "-" } else { "" };
+
+      Ok(Some(match format!("{}{}", sign, number).replace('_', "").parse() {
+        Ok(n) => Lit::Int(n),
+        Err(_) => Lit::Verbatim(format!("{}{}", sign, number)),
+      }))
+    }
+    None if neg => parser.error("expected a number"),
+    None => Ok(None),
+  }
+}
@@ -1,12 +1,16 @@
 use std::fmt::{self, Write};
 
 use crate::body::Body;
+use crate::control_flow::{fmt_label, label_name};
+use crate::expr::Expr;
 use crate::formatter::{Formatter, Format};
+use crate::stmt::{Let, Stmt};
 
 
 /// Defines a code block. This is used to define a function body.
 #[derive(Debug, Clone)]
 pub struct Block {
+    label: Option<String>,
     before: Option<String>,
     after: Option<String>,
     body: Vec<Body>,
@@ -17,6 +21,7 @@ impl Block {
     /// Returns an empty code block.
     pub fn new(before: impl Into<String>) -> Self {
         Block {
+            label: None,
             before: Some(before.into()),
             after: None,
             body: vec![],
@@ -32,30 +37,71 @@ impl Block {
         self
     }
 
+    /// Push a statement to the code block.
+    pub fn push_stmt(&mut self, stmt: impl Into<Stmt>) -> &mut Self {
+        self.body.push(Body::Stmt(stmt.into()));
+        self
+    }
+
+    /// Push an expression without a trailing semicolon to the code block.
+    pub fn push_expr(&mut self, expr: impl Into<Expr>) -> &mut Self {
+        self.push_stmt(Stmt::Expr(expr.into()))
+    }
+
+    /// Push a new `let` binding, returning a mutable reference to it.
+    pub fn new_let(&mut self, pat: impl Into<String>) -> &mut Let {
+        self.push_stmt(Let::new(pat));
+
+        match self.body.last_mut().unwrap() {
+            Body::Stmt(Stmt::Let(v)) => v,
+            _ => unreachable!(),
+        }
+    }
+
     /// Push a nested block to this block.
     pub fn push_block(&mut self, block: Block) -> &mut Self {
         self.body.push(Body::Block(block));
         self
     }
 
+    /// Set the block label, e.g. `'a: { ... }`. The leading `'` is optional.
+    pub fn label(&mut self, label: impl Into<String>) -> &mut Self {
+        self.label = Some(label_name(label));
+        self
+    }
+
     /// Add a snippet after the block.
     pub fn after(&mut self, after: impl Into<String>) -> &mut Self {
         self.after = Some(after.into());
         self
     }
-}
 
+    /// Returns true if the block has no contents.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.body.is_empty()
+    }
+
+    /// Returns the expression if the block consists of a single expression
+    /// without a trailing semicolon.
+    pub(crate) fn single_expr(&self) -> Option<&Expr> {
+        match &self.body[..] {
+            [Body::Stmt(Stmt::Expr(expr))] => Some(expr),
+            _ => None,
+        }
+    }
+
+    /// Formats the block without a trailing newline, for use as an
+    /// expression.
+    pub(crate) fn fmt_inline(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt_label(&self.label, fmt)?;
 
-impl Format for Block {
-    /// Formats the block using the given formatter.
-    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
         if let Some(ref before) = self.before {
             write!(fmt, "{}", before)?;
         }
 
         // Inlined `Formatter::fmt`
 
-        if !fmt.is_start_of_line() {
+        if fmt.needs_space() {
             write!(fmt, " ")?;
         }
 
@@ -75,6 +121,28 @@ impl Format for Block {
             write!(fmt, "{}", after)?;
         }
 
+        Ok(())
+    }
+}
+
+
+/// Returns a bare block, `{ ... }`.
+impl Default for Block {
+    fn default() -> Self {
+        Block {
+            label: None,
+            before: None,
+            after: None,
+            body: vec![],
+        }
+    }
+}
+
+
+impl Format for Block {
+    /// Formats the block using the given formatter.
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        self.fmt_inline(fmt)?;
         writeln!(fmt)
     }
 }
@@ -2,12 +2,14 @@ use std::fmt::{self, Write};
 
 use crate::block::Block;
 use crate::formatter::{Formatter, Format};
+use crate::stmt::Stmt;
 
 
 #[derive(Debug, Clone)]
 pub enum Body {
     String(String),
     Block(Block),
+    Stmt(Stmt),
 }
 
 
@@ -16,6 +18,7 @@ impl Format for Body {
         match self {
             Body::String(s) => writeln!(fmt, "{}", s),
             Body::Block(b) => b.fmt(fmt),
+            Body::Stmt(s) => s.fmt(fmt),
         }
     }
 }
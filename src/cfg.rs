@@ -0,0 +1,168 @@
+use std::fmt;
+
+use crate::attr_arg::{Lit, Meta, MetaList};
+use crate::parse::{ParseError, Parser};
+
+
+/// A configuration predicate, as used by `#[cfg]` and `#[cfg_attr]`.
+///
+/// ```
+/// use codegen::Cfg;
+///
+/// let cfg = Cfg::all([Cfg::feature("serde"), Cfg::not(Cfg::target_os("windows"))]);
+///
+/// assert_eq!(cfg.to_string(), r#"all(feature = "serde", not(target_os = "windows"))"#);
+/// assert_eq!(cfg.features(), ["serde"]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Cfg {
+    /// A configuration option set by name, e.g. `unix` or `test`.
+    Name(String),
+
+    /// A key-value configuration option, e.g. `feature = "serde"`.
+    KeyValue(String, String),
+
+    /// `all(...)`, true if every predicate is true.
+    All(Vec<Cfg>),
+
+    /// `any(...)`, true if at least one predicate is true.
+    Any(Vec<Cfg>),
+
+    /// `not(...)`
+    Not(Box<Cfg>),
+}
+
+
+impl Cfg {
+    /// Return a configuration option set by name, e.g. `unix`.
+    pub fn name(name: impl Into<String>) -> Self {
+        Cfg::Name(name.into())
+    }
+
+    /// Return a key-value configuration option, e.g. `panic = "abort"`.
+    pub fn key_value(key: impl Into<String>, value: impl Into<String>) -> Self {
+        Cfg::KeyValue(key.into(), value.into())
+    }
+
+    /// Return `feature = "name"`.
+    pub fn feature(name: impl Into<String>) -> Self {
+        Cfg::key_value("feature", name)
+    }
+
+    /// Return `target_os = "os"`.
+    pub fn target_os(os: impl Into<String>) -> Self {
+        Cfg::key_value("target_os", os)
+    }
+
+    /// Return `target_arch = "arch"`.
+    pub fn target_arch(arch: impl Into<String>) -> Self {
+        Cfg::key_value("target_arch", arch)
+    }
+
+    /// Return `all(...)` of the given predicates.
+    pub fn all(preds: impl IntoIterator<Item = Cfg>) -> Self {
+        Cfg::All(preds.into_iter().collect())
+    }
+
+    /// Return `any(...)` of the given predicates.
+    pub fn any(preds: impl IntoIterator<Item = Cfg>) -> Self {
+        Cfg::Any(preds.into_iter().collect())
+    }
+
+    /// Return `not(pred)`.
+    // Named after the predicate, not `std::ops::Not`
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(pred: Cfg) -> Self {
+        Cfg::Not(Box::new(pred))
+    }
+
+    /// Parse a predicate, e.g. `any(unix, feature = "std")`.
+    pub fn parse(src: &str) -> Result<Self, ParseError> {
+        let list = MetaList::parse(src)?;
+
+        match list.items() {
+            [meta] => match Cfg::from_meta(meta) {
+                Some(cfg) => Ok(cfg),
+                None => Parser::new(src).error("invalid cfg predicate"),
+            },
+            _ => Parser::new(src).error("expected a single cfg predicate"),
+        }
+    }
+
+    /// Returns the features the predicate refers to, in order of
+    /// appearance.
+    pub fn features(&self) -> Vec<&str> {
+        let mut features = vec![];
+        self.collect_features(&mut features);
+        features
+    }
+
+    fn collect_features<'a>(&'a self, features: &mut Vec<&'a str>) {
+        match self {
+            Cfg::KeyValue(key, value) if key == "feature" => {
+                if !features.contains(&&value[..]) {
+                    features.push(value);
+                }
+            }
+            Cfg::Name(_) | Cfg::KeyValue(..) => {}
+            Cfg::All(preds) | Cfg::Any(preds) => {
+                for pred in preds {
+                    pred.collect_features(features);
+                }
+            }
+            Cfg::Not(pred) => pred.collect_features(features),
+        }
+    }
+
+    /// Converts a meta item, e.g. `feature = "std"`, to a predicate.
+    pub(crate) fn from_meta(meta: &Meta) -> Option<Self> {
+        match meta {
+            Meta::Path(name) => Some(Cfg::name(name)),
+            Meta::NameValue(key, Lit::Str(value)) => Some(Cfg::key_value(key, value)),
+            Meta::List(name, list) => {
+                let preds = list.items()
+                    .iter()
+                    .map(Cfg::from_meta)
+                    .collect::<Option<Vec<_>>>()?;
+
+                match &name[..] {
+                    "all" => Some(Cfg::All(preds)),
+                    "any" => Some(Cfg::Any(preds)),
+                    "not" if preds.len() == 1 => {
+                        Some(Cfg::not(preds.into_iter().next().unwrap()))
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Converts the predicate to a meta item.
+    pub(crate) fn to_meta(&self) -> Meta {
+        match self {
+            Cfg::Name(name) => Meta::Path(name.clone()),
+            Cfg::KeyValue(key, value) => Meta::NameValue(key.clone(), Lit::from(value)),
+            Cfg::All(preds) => Cfg::list_meta("all", preds),
+            Cfg::Any(preds) => Cfg::list_meta("any", preds),
+            Cfg::Not(pred) => Cfg::list_meta("not", std::slice::from_ref(&**pred)),
+        }
+    }
+
+    fn list_meta(name: &str, preds: &[Cfg]) -> Meta {
+        let mut list = MetaList::new();
+
+        for pred in preds {
+            list.push(pred.to_meta());
+        }
+
+        Meta::List(name.to_string(), list)
+    }
+}
+
+
+impl fmt::Display for Cfg {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.to_meta())
+    }
+}
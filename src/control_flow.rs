@@ -0,0 +1,314 @@
+use std::fmt::{self, Write};
+
+use crate::block::Block;
+use crate::expr::Expr;
+use crate::formatter::Formatter;
+use crate::pat::Pat;
+
+
+/// Defines an `if` expression, including any `else if` and `else` branches.
+///
+/// ```
+/// use codegen::If;
+///
+/// let mut expr = If::new("x > 0");
+/// expr.then().line("positive()");
+/// expr.else_if("x < 0").line("negative()");
+/// expr.r#else().line("zero()");
+/// ```
+#[derive(Debug, Clone)]
+pub struct If {
+    branches: Vec<(Cond, Block)>,
+    r#else: Option<Block>,
+}
+
+
+/// Defines a `loop` expression.
+#[derive(Debug, Clone)]
+pub struct Loop {
+    label: Option<String>,
+    body: Block,
+}
+
+
+/// Defines a `while` or `while let` loop.
+#[derive(Debug, Clone)]
+pub struct While {
+    label: Option<String>,
+    cond: Cond,
+    body: Block,
+}
+
+
+/// Defines a `for` loop.
+#[derive(Debug, Clone)]
+pub struct For {
+    label: Option<String>,
+    pat: Pat,
+    iter: Expr,
+    body: Block,
+}
+
+
+/// Condition of an `if` or `while`, optionally destructuring with `let`.
+#[derive(Debug, Clone)]
+struct Cond {
+    pat: Option<Pat>,
+    expr: Expr,
+}
+
+
+impl If {
+    /// Return a new `if` expression with the given condition.
+    pub fn new(cond: impl Into<Expr>) -> Self {
+        If {
+            branches: vec![(Cond::new(None, cond), Block::default())],
+            r#else: None,
+        }
+    }
+
+    /// Return a new `if let` expression.
+    pub fn new_let<P, E>(pat: P, expr: E) -> Self
+    where
+        P: Into<Pat>,
+        E: Into<Expr>,
+    {
+        If {
+            branches: vec![(Cond::new(Some(pat.into()), expr), Block::default())],
+            r#else: None,
+        }
+    }
+
+    /// Returns a mutable reference to the block run when the first condition
+    /// holds.
+    pub fn then(&mut self) -> &mut Block {
+        &mut self.branches[0].1
+    }
+
+    /// Push an `else if` branch, returning a mutable reference to its block.
+    pub fn else_if(&mut self, cond: impl Into<Expr>) -> &mut Block {
+        self.push_branch(Cond::new(None, cond))
+    }
+
+    /// Push an `else if let` branch, returning a mutable reference to its
+    /// block.
+    pub fn else_if_let<P, E>(&mut self, pat: P, expr: E) -> &mut Block
+    where
+        P: Into<Pat>,
+        E: Into<Expr>,
+    {
+        self.push_branch(Cond::new(Some(pat.into()), expr))
+    }
+
+    /// Returns a mutable reference to the `else` block, creating it if it
+    /// does not exist.
+    pub fn r#else(&mut self) -> &mut Block {
+        self.r#else.get_or_insert_with(Block::default)
+    }
+
+    fn push_branch(&mut self, cond: Cond) -> &mut Block {
+        self.branches.push((cond, Block::default()));
+        &mut self.branches.last_mut().unwrap().1
+    }
+
+    /// Formats the `if` expression using the given formatter.
+    pub fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        for (i, (cond, block)) in self.branches.iter().enumerate() {
+            if i != 0 {
+                write!(fmt, " else ")?;
+            }
+
+            write!(fmt, "if ")?;
+            cond.fmt(fmt)?;
+            block.fmt_inline(fmt)?;
+        }
+
+        if let Some(block) = &self.r#else {
+            write!(fmt, " else")?;
+            block.fmt_inline(fmt)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<If> for Expr {
+    fn from(src: If) -> Self {
+        Expr::If(Box::new(src))
+    }
+}
+
+
+impl Loop {
+    /// Return a new, empty `loop`.
+    pub fn new() -> Self {
+        Loop {
+            label: None,
+            body: Block::default(),
+        }
+    }
+
+    /// Set the loop label. The leading `'` is optional.
+    pub fn label(&mut self, label: impl Into<String>) -> &mut Self {
+        self.label = Some(label_name(label));
+        self
+    }
+
+    /// Returns a mutable reference to the loop body.
+    pub fn body(&mut self) -> &mut Block {
+        &mut self.body
+    }
+
+    /// Formats the loop using the given formatter.
+    pub fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt_label(&self.label, fmt)?;
+        write!(fmt, "loop")?;
+        self.body.fmt_inline(fmt)
+    }
+}
+
+impl Default for Loop {
+    fn default() -> Self {
+        Loop::new()
+    }
+}
+
+impl From<Loop> for Expr {
+    fn from(src: Loop) -> Self {
+        Expr::Loop(Box::new(src))
+    }
+}
+
+
+impl While {
+    /// Return a new `while` loop with the given condition.
+    pub fn new(cond: impl Into<Expr>) -> Self {
+        While {
+            label: None,
+            cond: Cond::new(None, cond),
+            body: Block::default(),
+        }
+    }
+
+    /// Return a new `while let` loop.
+    pub fn new_let<P, E>(pat: P, expr: E) -> Self
+    where
+        P: Into<Pat>,
+        E: Into<Expr>,
+    {
+        While {
+            label: None,
+            cond: Cond::new(Some(pat.into()), expr),
+            body: Block::default(),
+        }
+    }
+
+    /// Set the loop label. The leading `'` is optional.
+    pub fn label(&mut self, label: impl Into<String>) -> &mut Self {
+        self.label = Some(label_name(label));
+        self
+    }
+
+    /// Returns a mutable reference to the loop body.
+    pub fn body(&mut self) -> &mut Block {
+        &mut self.body
+    }
+
+    /// Formats the loop using the given formatter.
+    pub fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt_label(&self.label, fmt)?;
+        write!(fmt, "while ")?;
+        self.cond.fmt(fmt)?;
+        self.body.fmt_inline(fmt)
+    }
+}
+
+impl From<While> for Expr {
+    fn from(src: While) -> Self {
+        Expr::While(Box::new(src))
+    }
+}
+
+
+impl For {
+    /// Return a new `for` loop binding `pat` to each item of `iter`.
+    pub fn new<P, E>(pat: P, iter: E) -> Self
+    where
+        P: Into<Pat>,
+        E: Into<Expr>,
+    {
+        For {
+            label: None,
+            pat: pat.into(),
+            iter: iter.into(),
+            body: Block::default(),
+        }
+    }
+
+    /// Set the loop label. The leading `'` is optional.
+    pub fn label(&mut self, label: impl Into<String>) -> &mut Self {
+        self.label = Some(label_name(label));
+        self
+    }
+
+    /// Returns a mutable reference to the loop body.
+    pub fn body(&mut self) -> &mut Block {
+        &mut self.body
+    }
+
+    /// Formats the loop using the given formatter.
+    pub fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt_label(&self.label, fmt)?;
+        write!(fmt, "for ")?;
+        self.pat.fmt(fmt)?;
+        write!(fmt, " in ")?;
+        self.iter.fmt(fmt)?;
+        self.body.fmt_inline(fmt)
+    }
+}
+
+impl From<For> for Expr {
+    fn from(src: For) -> Self {
+        Expr::For(Box::new(src))
+    }
+}
+
+
+impl Cond {
+    fn new(pat: Option<Pat>, expr: impl Into<Expr>) -> Self {
+        Cond {
+            pat,
+            expr: expr.into(),
+        }
+    }
+
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        if let Some(pat) = &self.pat {
+            write!(fmt, "let ")?;
+            pat.fmt(fmt)?;
+            write!(fmt, " = ")?;
+        }
+
+        self.expr.fmt(fmt)
+    }
+}
+
+
+/// Prepends the `'` to a label if it is missing.
+pub(crate) fn label_name(label: impl Into<String>) -> String {
+    let mut label = label.into();
+
+    if !label.starts_with('\'') {
+        label.insert(0, '\'');
+    }
+
+    label
+}
+
+pub(crate) fn fmt_label(label: &Option<String>, fmt: &mut Formatter) -> fmt::Result {
+    if let Some(label) = label {
+        write!(fmt, "{}: ", label)?;
+    }
+
+    Ok(())
+}
@@ -1,5 +1,7 @@
 use std::fmt::{self, Write};
 
+use crate::attr::Attr;
+use crate::cfg::Cfg;
 use crate::formatter::{Formatter, Format};
 
 use crate::r#enum::EnumVariant;
@@ -11,6 +13,7 @@ pub struct DisVariant {
     name: String,
     /// discriminant
     dis: Option<String>,
+    attrs: Vec<Attr>,
 }
 
 
@@ -20,6 +23,7 @@ impl EnumVariant for DisVariant {
         Self {
             name: name.into(),
             dis: None,
+            attrs: vec![],
         }
     }
 }
@@ -31,11 +35,38 @@ impl DisVariant {
         self.dis = Some(dis.into());
         self
     }
+
+    /// Push an attribute to the variant.
+    pub fn push_attr(&mut self, attr: Attr) -> &mut Self {
+        self.attrs.push(attr);
+        self
+    }
+
+    /// Create an attribute on the variant.
+    pub fn new_attr(&mut self, name: impl Into<String>) -> &mut Attr {
+        self.push_attr(Attr::new(name));
+
+        self.attrs.last_mut().unwrap()
+    }
+
+    /// Push a `#[cfg(pred)]` attribute.
+    pub fn cfg(&mut self, pred: Cfg) -> &mut Self {
+        self.push_attr(Attr::cfg(pred))
+    }
+
+    /// Push a `#[cfg_attr(pred, attr)]` attribute.
+    pub fn cfg_attr(&mut self, pred: Cfg, attr: Attr) -> &mut Self {
+        self.push_attr(Attr::cfg_attr(pred, attr))
+    }
 }
 
 impl Format for DisVariant {
     /// Formats the variant using the given formatter.
     fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        for attr in &self.attrs {
+            attr.fmt(fmt, false)?;
+        }
+
         write!(fmt, "{}", self.name)?;
         if let Some(dis) = &self.dis {
             write!(fmt, " = {}", dis)?;
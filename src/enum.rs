@@ -1,8 +1,13 @@
 use std::fmt;
 
+use crate::attr::Attr;
+use crate::cfg::Cfg;
 use crate::formatter::{Formatter, Format};
+use crate::generic_param::GenericParam;
 use crate::type_def::TypeDef;
+use crate::where_predicate::WherePredicate;
 use crate::variant::Variant;
+use crate::visibility::Visibility;
 
 use crate::r#type::Type;
 
@@ -34,28 +39,72 @@ impl<V: EnumVariant> Enum<V> {
         &self.type_def.ty
     }
 
+    /// Returns the visibility.
+    pub fn visibility(&self) -> &Visibility {
+        self.type_def.visibility()
+    }
+
     /// Set the enum visibility.
-    pub fn vis(&mut self, vis: impl Into<String>) -> &mut Self {
+    pub fn vis(&mut self, vis: impl Into<Visibility>) -> &mut Self {
         self.type_def.vis(vis);
         self
     }
 
     /// Add a generic to the enum.
-    pub fn generic(&mut self, name: impl Into<Type>) -> &mut Self {
-        self.type_def.ty.generic(name);
+    pub fn generic(&mut self, param: impl Into<GenericParam>) -> &mut Self {
+        self.type_def.generic(param.into());
         self
     }
 
     /// Add a `where` bound to the enum.
     pub fn bound<S, T>(&mut self, name: S, ty: T) -> &mut Self
     where
-        S: Into<String>,
+        S: Into<Type>,
         T: Into<Type>,
     {
         self.type_def.bound(name, ty);
         self
     }
 
+    /// Push a `where` predicate to the enum.
+    ///
+    /// Bounds on a type that is already constrained are merged into the
+    /// existing predicate.
+    pub fn push_predicate(&mut self, pred: WherePredicate) -> &mut Self {
+        self.type_def.push_predicate(pred);
+        self
+    }
+
+    /// Returns the `where` predicate on the given type, creating it if it
+    /// does not exist.
+    pub fn new_predicate<T>(&mut self, bounded: T) -> &mut WherePredicate
+    where
+        T: Into<Type>,
+    {
+        self.type_def.new_predicate(bounded)
+    }
+
+    /// Push an outer attribute.
+    pub fn push_attr(&mut self, attr: Attr) -> &mut Self {
+        self.type_def.push_attr(attr);
+        self
+    }
+
+    /// Create an outer attribute.
+    pub fn new_attr(&mut self, name: impl Into<String>) -> &mut Attr {
+        self.type_def.new_attr(name)
+    }
+
+    /// Push a `#[cfg(pred)]` attribute.
+    pub fn cfg(&mut self, pred: Cfg) -> &mut Self {
+        self.push_attr(Attr::cfg(pred))
+    }
+
+    /// Push a `#[cfg_attr(pred, attr)]` attribute.
+    pub fn cfg_attr(&mut self, pred: Cfg, attr: Attr) -> &mut Self {
+        self.push_attr(Attr::cfg_attr(pred, attr))
+    }
+
     /// Set the enum documentation.
     pub fn doc(&mut self, docs: impl Into<String>) -> &mut Self {
         self.type_def.doc(docs);
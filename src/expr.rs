@@ -0,0 +1,913 @@
+use std::fmt::{self, Write};
+
+use crate::block::Block;
+use crate::control_flow::{For, If, Loop, While, label_name};
+use crate::formatter::Formatter;
+use crate::macro_call::MacroCall;
+use crate::r#match::Match;
+
+use crate::r#type::Type;
+
+
+/// Defines an expression.
+///
+/// Operands are wrapped in parentheses when required by operator
+/// precedence.
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub enum Expr {
+    /// A path, e.g. `foo` or `std::mem::swap`.
+    Path(String),
+
+    /// A literal, e.g. `1`, `"foo"` or `true`.
+    Lit(String),
+
+    /// Raw source text.
+    ///
+    /// Text containing whitespace outside of brackets and string literals is
+    /// wrapped in parentheses when used as an operand.
+    Raw(String),
+
+    /// A function call, e.g. `foo(a, b)`.
+    Call {
+        /// Called function
+        func: Box<Expr>,
+        /// Arguments
+        args: Vec<Expr>,
+    },
+
+    /// A method call, e.g. `foo.bar::<T>(a, b)`.
+    MethodCall {
+        /// Receiver of the call
+        receiver: Box<Expr>,
+        /// Method name
+        method: String,
+        /// Generic arguments passed with the turbofish syntax
+        turbofish: Vec<Type>,
+        /// Arguments
+        args: Vec<Expr>,
+    },
+
+    /// A field access, e.g. `foo.bar` or `foo.0`.
+    Field {
+        /// Accessed value
+        base: Box<Expr>,
+        /// Field name or tuple index
+        name: String,
+    },
+
+    /// An index operation, e.g. `foo[i]`.
+    Index {
+        /// Indexed value
+        base: Box<Expr>,
+        /// Index
+        index: Box<Expr>,
+    },
+
+    /// A binary operation, e.g. `a + b` or `a += b`.
+    Binary {
+        /// Operator
+        op: BinOp,
+        /// Left-hand side
+        lhs: Box<Expr>,
+        /// Right-hand side
+        rhs: Box<Expr>,
+    },
+
+    /// A unary operation, e.g. `!a` or `&mut a`.
+    Unary {
+        /// Operator
+        op: UnOp,
+        /// Operand
+        expr: Box<Expr>,
+    },
+
+    /// A cast, e.g. `a as u32`.
+    Cast {
+        /// Cast value
+        expr: Box<Expr>,
+        /// Target type
+        ty: Type,
+    },
+
+    /// The `?` operator.
+    Try(Box<Expr>),
+
+    /// An `.await` expression.
+    Await(Box<Expr>),
+
+    /// A range, e.g. `a..b` or `..=b`.
+    Range {
+        /// Start of the range
+        start: Option<Box<Expr>>,
+        /// End of the range
+        end: Option<Box<Expr>>,
+        /// Whether the range includes its end, i.e. `..=`
+        inclusive: bool,
+    },
+
+    /// A tuple, e.g. `(a, b)`.
+    Tuple(Vec<Expr>),
+
+    /// An array, e.g. `[a, b]`.
+    Array(Vec<Expr>),
+
+    /// A closure.
+    Closure(Box<Closure>),
+
+    /// A block, e.g. `{ ... }` or `unsafe { ... }`.
+    Block(Block),
+
+    /// A `match` expression.
+    Match(Box<Match>),
+
+    /// An `if` expression.
+    If(Box<If>),
+
+    /// A `loop` expression.
+    Loop(Box<Loop>),
+
+    /// A `while` loop.
+    While(Box<While>),
+
+    /// A `for` loop.
+    For(Box<For>),
+
+    /// A macro invocation, e.g. `vec![a, b]`.
+    Macro(Box<MacroCall>),
+
+    /// A `return` expression.
+    Return(Option<Box<Expr>>),
+
+    /// A `break` expression, e.g. `break 'outer value`.
+    Break {
+        /// Label of the exited loop or block
+        label: Option<String>,
+        /// Value of the exited loop or block
+        value: Option<Box<Expr>>,
+    },
+
+    /// A `continue` expression.
+    Continue(Option<String>),
+}
+
+
+/// A binary operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinOp {
+    /// `+`
+    Add,
+    /// `-`
+    Sub,
+    /// `*`
+    Mul,
+    /// `/`
+    Div,
+    /// `%`
+    Rem,
+    /// `&&`
+    And,
+    /// `||`
+    Or,
+    /// `&`
+    BitAnd,
+    /// `|`
+    BitOr,
+    /// `^`
+    BitXor,
+    /// `<<`
+    Shl,
+    /// `>>`
+    Shr,
+    /// `==`
+    Eq,
+    /// `!=`
+    Ne,
+    /// `<`
+    Lt,
+    /// `<=`
+    Le,
+    /// `>`
+    Gt,
+    /// `>=`
+    Ge,
+    /// `=`
+    Assign,
+    /// `+=`
+    AddAssign,
+    /// `-=`
+    SubAssign,
+    /// `*=`
+    MulAssign,
+    /// `/=`
+    DivAssign,
+    /// `%=`
+    RemAssign,
+    /// `&=`
+    BitAndAssign,
+    /// `|=`
+    BitOrAssign,
+    /// `^=`
+    BitXorAssign,
+    /// `<<=`
+    ShlAssign,
+    /// `>>=`
+    ShrAssign,
+}
+
+
+/// A unary operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnOp {
+    /// `-`
+    Neg,
+    /// `!`
+    Not,
+    /// `*`
+    Deref,
+    /// `&`
+    Ref,
+    /// `&mut`
+    RefMut,
+}
+
+
+/// Defines a closure, e.g. `move |a: u32| a + 1`.
+#[derive(Debug, Clone)]
+pub struct Closure {
+    is_move: bool,
+    params: Vec<(String, Option<Type>)>,
+    ret: Option<Type>,
+    body: Expr,
+}
+
+
+// Operator precedence, from loosest to tightest binding.
+const PREC_JUMP: u8 = 1;
+const PREC_ASSIGN: u8 = 2;
+const PREC_RANGE: u8 = 3;
+const PREC_OR: u8 = 4;
+const PREC_AND: u8 = 5;
+const PREC_CMP: u8 = 6;
+const PREC_BIT_OR: u8 = 7;
+const PREC_BIT_XOR: u8 = 8;
+const PREC_BIT_AND: u8 = 9;
+const PREC_SHIFT: u8 = 10;
+const PREC_ADD: u8 = 11;
+const PREC_MUL: u8 = 12;
+const PREC_CAST: u8 = 13;
+const PREC_PREFIX: u8 = 14;
+const PREC_POSTFIX: u8 = 15;
+const PREC_ATOM: u8 = 16;
+
+
+impl Expr {
+    /// Return a new path expression.
+    pub fn path(path: impl Into<String>) -> Self {
+        Expr::Path(path.into())
+    }
+
+    /// Return a new literal expression. The literal is written verbatim.
+    pub fn lit(lit: impl ToString) -> Self {
+        Expr::Lit(lit.to_string())
+    }
+
+    /// Return a new string literal, escaping the given value.
+    pub fn str(value: impl AsRef<str>) -> Self {
+        Expr::Lit(format!("{:?}", value.as_ref()))
+    }
+
+    /// Return a new expression from raw source text.
+    pub fn raw(src: impl Into<String>) -> Self {
+        Expr::Raw(src.into())
+    }
+
+    /// Return a new function call.
+    pub fn call<F, I, T>(func: F, args: I) -> Self
+    where
+        F: Into<Expr>,
+        I: IntoIterator<Item = T>,
+        T: Into<Expr>,
+    {
+        Expr::Call {
+            func: Box::new(func.into()),
+            args: collect(args),
+        }
+    }
+
+    /// Return a new binary operation.
+    pub fn binary<L, R>(lhs: L, op: BinOp, rhs: R) -> Self
+    where
+        L: Into<Expr>,
+        R: Into<Expr>,
+    {
+        Expr::Binary {
+            op,
+            lhs: Box::new(lhs.into()),
+            rhs: Box::new(rhs.into()),
+        }
+    }
+
+    /// Return a new unary operation.
+    pub fn unary(op: UnOp, expr: impl Into<Expr>) -> Self {
+        Expr::Unary {
+            op,
+            expr: Box::new(expr.into()),
+        }
+    }
+
+    /// Return a new `return` expression.
+    pub fn ret(expr: impl Into<Expr>) -> Self {
+        Expr::Return(Some(Box::new(expr.into())))
+    }
+
+    /// Return a new `break` expression.
+    pub fn r#break() -> Self {
+        Expr::Break {
+            label: None,
+            value: None,
+        }
+    }
+
+    /// Return a new `break` expression exiting with the given value.
+    pub fn break_with(value: impl Into<Expr>) -> Self {
+        Expr::Break {
+            label: None,
+            value: Some(Box::new(value.into())),
+        }
+    }
+
+    /// Return a new `continue` expression.
+    pub fn r#continue() -> Self {
+        Expr::Continue(None)
+    }
+
+    /// Return a new range `start..end`.
+    pub fn range<S, E>(start: S, end: E) -> Self
+    where
+        S: Into<Expr>,
+        E: Into<Expr>,
+    {
+        Expr::Range {
+            start: Some(Box::new(start.into())),
+            end: Some(Box::new(end.into())),
+            inclusive: false,
+        }
+    }
+
+    /// Return a new tuple.
+    pub fn tuple<I, T>(elems: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<Expr>,
+    {
+        Expr::Tuple(collect(elems))
+    }
+
+    /// Return a new array.
+    pub fn array<I, T>(elems: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<Expr>,
+    {
+        Expr::Array(collect(elems))
+    }
+
+    /// Call a method on this expression.
+    pub fn method<I, T>(self, method: impl Into<String>, args: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<Expr>,
+    {
+        Expr::MethodCall {
+            receiver: Box::new(self),
+            method: method.into(),
+            turbofish: vec![],
+            args: collect(args),
+        }
+    }
+
+    /// Add a turbofish generic argument to a method call.
+    ///
+    /// # Panics
+    ///
+    /// The expression must be a method call.
+    pub fn turbofish(mut self, ty: impl Into<Type>) -> Self {
+        match &mut self {
+            Expr::MethodCall { turbofish, .. } => turbofish.push(ty.into()),
+            _ => panic!("turbofish is only supported on method calls"),
+        }
+
+        self
+    }
+
+    /// Access a field of this expression.
+    pub fn field(self, name: impl ToString) -> Self {
+        Expr::Field {
+            base: Box::new(self),
+            name: name.to_string(),
+        }
+    }
+
+    /// Index this expression.
+    pub fn index(self, index: impl Into<Expr>) -> Self {
+        Expr::Index {
+            base: Box::new(self),
+            index: Box::new(index.into()),
+        }
+    }
+
+    /// Cast this expression to the given type.
+    pub fn cast(self, ty: impl Into<Type>) -> Self {
+        Expr::Cast {
+            expr: Box::new(self),
+            ty: ty.into(),
+        }
+    }
+
+    /// Apply the `?` operator to this expression.
+    pub fn r#try(self) -> Self {
+        Expr::Try(Box::new(self))
+    }
+
+    /// Await this expression.
+    pub fn r#await(self) -> Self {
+        Expr::Await(Box::new(self))
+    }
+
+    /// Set the label targeted by a `break` or `continue`. The leading `'` is
+    /// optional.
+    ///
+    /// # Panics
+    ///
+    /// The expression must be a `break` or `continue`.
+    pub fn label(mut self, name: impl Into<String>) -> Self {
+        match &mut self {
+            Expr::Break { label, .. } | Expr::Continue(label) => {
+                *label = Some(label_name(name));
+            }
+            _ => panic!("only `break` and `continue` can target a label"),
+        }
+
+        self
+    }
+
+    /// Returns true if the expression ends with a block, e.g. `match`, and
+    /// needs no semicolon as a statement.
+    pub(crate) fn is_block_like(&self) -> bool {
+        matches!(
+            self,
+            Expr::Block(_)
+                | Expr::Match(_)
+                | Expr::If(_)
+                | Expr::Loop(_)
+                | Expr::While(_)
+                | Expr::For(_)
+        ) || matches!(self, Expr::Macro(m) if m.is_braced())
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::Path(_) | Expr::Tuple(_) | Expr::Array(_) => PREC_ATOM,
+            Expr::Block(_) | Expr::Match(_) | Expr::If(_) => PREC_ATOM,
+            Expr::Loop(_) | Expr::While(_) | Expr::For(_) => PREC_ATOM,
+            Expr::Continue(_) | Expr::Macro(_) => PREC_ATOM,
+            Expr::Lit(lit) if lit.starts_with('-') => PREC_PREFIX,
+            Expr::Lit(_) => PREC_ATOM,
+            Expr::Raw(src) if is_atomic(src) => PREC_ATOM,
+            Expr::Raw(_) => PREC_JUMP,
+            Expr::Call { .. }
+            | Expr::MethodCall { .. }
+            | Expr::Field { .. }
+            | Expr::Index { .. }
+            | Expr::Try(_)
+            | Expr::Await(_) => PREC_POSTFIX,
+            Expr::Unary { .. } => PREC_PREFIX,
+            Expr::Cast { .. } => PREC_CAST,
+            Expr::Binary { op, .. } => op.precedence(),
+            Expr::Range { .. } => PREC_RANGE,
+            Expr::Closure(_) | Expr::Return(_) | Expr::Break { .. } => PREC_JUMP,
+        }
+    }
+
+    /// Formats the expression using the given formatter.
+    pub fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            Expr::Path(s) => fmt.write_path(s),
+            Expr::Lit(s) | Expr::Raw(s) => write!(fmt, "{}", s),
+            Expr::Call { func, args } => {
+                func.fmt_operand(PREC_POSTFIX, fmt)?;
+                fmt_list("(", args, ")", fmt)
+            }
+            Expr::MethodCall { receiver, method, turbofish, args } => {
+                receiver.fmt_operand(PREC_POSTFIX, fmt)?;
+                write!(fmt, ".{}", method)?;
+
+                if !turbofish.is_empty() {
+                    write!(fmt, "::<")?;
+
+                    for (i, ty) in turbofish.iter().enumerate() {
+                        if i != 0 {
+                            write!(fmt, ", ")?;
+                        }
+                        ty.fmt(fmt)?;
+                    }
+
+                    write!(fmt, ">")?;
+                }
+
+                fmt_list("(", args, ")", fmt)
+            }
+            Expr::Field { base, name } => {
+                base.fmt_operand(PREC_POSTFIX, fmt)?;
+                write!(fmt, ".{}", name)
+            }
+            Expr::Index { base, index } => {
+                base.fmt_operand(PREC_POSTFIX, fmt)?;
+                write!(fmt, "[")?;
+                index.fmt(fmt)?;
+                write!(fmt, "]")
+            }
+            Expr::Binary { op, lhs, rhs } => {
+                let prec = op.precedence();
+
+                let (lhs_min, rhs_min) = match prec {
+                    PREC_ASSIGN => (prec + 1, prec),
+                    PREC_CMP => (prec + 1, prec + 1),
+                    _ => (prec, prec + 1),
+                };
+
+                // `a as u32 < b` would parse `<` as the start of generic
+                // arguments of `u32`
+                let starts_generics = *op == BinOp::Lt || *op == BinOp::Shl;
+
+                if starts_generics && lhs.precedence() >= lhs_min && lhs.ends_with_cast() {
+                    write!(fmt, "(")?;
+                    lhs.fmt(fmt)?;
+                    write!(fmt, ")")?;
+                } else {
+                    lhs.fmt_operand(lhs_min, fmt)?;
+                }
+
+                write!(fmt, " {} ", op.as_str())?;
+                rhs.fmt_operand(rhs_min, fmt)
+            }
+            Expr::Unary { op, expr } => {
+                write!(fmt, "{}", op.as_str())?;
+                expr.fmt_operand(PREC_PREFIX, fmt)
+            }
+            Expr::Cast { expr, ty } => {
+                expr.fmt_operand(PREC_CAST, fmt)?;
+                write!(fmt, " as ")?;
+                ty.fmt(fmt)
+            }
+            Expr::Try(expr) => {
+                expr.fmt_operand(PREC_POSTFIX, fmt)?;
+                write!(fmt, "?")
+            }
+            Expr::Await(expr) => {
+                expr.fmt_operand(PREC_POSTFIX, fmt)?;
+                write!(fmt, ".await")
+            }
+            Expr::Range { start, end, inclusive } => {
+                if let Some(start) = start {
+                    start.fmt_operand(PREC_RANGE + 1, fmt)?;
+                }
+
+                write!(fmt, "{}", if *inclusive { "..=" } else { ".." })?;
+
+                if let Some(end) = end {
+                    end.fmt_operand(PREC_RANGE + 1, fmt)?;
+                }
+
+                Ok(())
+            }
+            Expr::Tuple(elems) => {
+                fmt_list("(", elems, "", fmt)?;
+
+                if elems.len() == 1 {
+                    write!(fmt, ",")?;
+                }
+
+                write!(fmt, ")")
+            }
+            Expr::Array(elems) => fmt_list("[", elems, "]", fmt),
+            Expr::Closure(closure) => closure.fmt(fmt),
+            Expr::Block(block) => block.fmt_inline(fmt),
+            Expr::Match(m) => m.fmt(fmt),
+            Expr::If(v) => v.fmt(fmt),
+            Expr::Loop(v) => v.fmt(fmt),
+            Expr::While(v) => v.fmt(fmt),
+            Expr::For(v) => v.fmt(fmt),
+            Expr::Macro(v) => v.fmt_call(fmt),
+            Expr::Return(expr) => {
+                write!(fmt, "return")?;
+
+                if let Some(expr) = expr {
+                    write!(fmt, " ")?;
+                    expr.fmt(fmt)?;
+                }
+
+                Ok(())
+            }
+            Expr::Break { label, value } => {
+                write!(fmt, "break")?;
+
+                if let Some(label) = label {
+                    write!(fmt, " {}", label)?;
+                }
+
+                if let Some(value) = value {
+                    write!(fmt, " ")?;
+                    value.fmt(fmt)?;
+                }
+
+                Ok(())
+            }
+            Expr::Continue(label) => {
+                write!(fmt, "continue")?;
+
+                if let Some(label) = label {
+                    write!(fmt, " {}", label)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns true if the expression is written ending with the type of a
+    /// cast, e.g. `x + y as u32`.
+    fn ends_with_cast(&self) -> bool {
+        match self {
+            Expr::Cast { .. } => true,
+            Expr::Binary { rhs, .. } => rhs.ends_with_cast(),
+            _ => false,
+        }
+    }
+
+    /// Formats the expression, wrapping it in parentheses if it binds less
+    /// tightly than `min_prec`.
+    fn fmt_operand(&self, min_prec: u8, fmt: &mut Formatter) -> fmt::Result {
+        if self.precedence() < min_prec {
+            write!(fmt, "(")?;
+            self.fmt(fmt)?;
+            write!(fmt, ")")
+        } else {
+            self.fmt(fmt)
+        }
+    }
+}
+
+/// Converts the string to a raw expression.
+impl<'a> From<&'a str> for Expr {
+    fn from(src: &'a str) -> Self {
+        Expr::Raw(src.to_string())
+    }
+}
+
+impl From<String> for Expr {
+    fn from(src: String) -> Self {
+        Expr::Raw(src)
+    }
+}
+
+impl<'a> From<&'a String> for Expr {
+    fn from(src: &'a String) -> Self {
+        Expr::Raw(src.clone())
+    }
+}
+
+impl<'a> From<&'a Expr> for Expr {
+    fn from(src: &'a Expr) -> Self {
+        src.clone()
+    }
+}
+
+impl From<Block> for Expr {
+    fn from(src: Block) -> Self {
+        Expr::Block(src)
+    }
+}
+
+impl From<MacroCall> for Expr {
+    fn from(src: MacroCall) -> Self {
+        Expr::Macro(Box::new(src))
+    }
+}
+
+impl From<Closure> for Expr {
+    fn from(src: Closure) -> Self {
+        Expr::Closure(Box::new(src))
+    }
+}
+
+
+impl BinOp {
+    /// Returns the operator token.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BinOp::Add => "+",
+            BinOp::Sub => "-",
+            BinOp::Mul => "*",
+            BinOp::Div => "/",
+            BinOp::Rem => "%",
+            BinOp::And => "&&",
+            BinOp::Or => "||",
+            BinOp::BitAnd => "&",
+            BinOp::BitOr => "|",
+            BinOp::BitXor => "^",
+            BinOp::Shl => "<<",
+            BinOp::Shr => ">>",
+            BinOp::Eq => "==",
+            BinOp::Ne => "!=",
+            BinOp::Lt => "<",
+            BinOp::Le => "<=",
+            BinOp::Gt => ">",
+            BinOp::Ge => ">=",
+            BinOp::Assign => "=",
+            BinOp::AddAssign => "+=",
+            BinOp::SubAssign => "-=",
+            BinOp::MulAssign => "*=",
+            BinOp::DivAssign => "/=",
+            BinOp::RemAssign => "%=",
+            BinOp::BitAndAssign => "&=",
+            BinOp::BitOrAssign => "|=",
+            BinOp::BitXorAssign => "^=",
+            BinOp::ShlAssign => "<<=",
+            BinOp::ShrAssign => ">>=",
+        }
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            BinOp::Mul | BinOp::Div | BinOp::Rem => PREC_MUL,
+            BinOp::Add | BinOp::Sub => PREC_ADD,
+            BinOp::Shl | BinOp::Shr => PREC_SHIFT,
+            BinOp::BitAnd => PREC_BIT_AND,
+            BinOp::BitXor => PREC_BIT_XOR,
+            BinOp::BitOr => PREC_BIT_OR,
+            BinOp::Eq | BinOp::Ne | BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge => {
+                PREC_CMP
+            }
+            BinOp::And => PREC_AND,
+            BinOp::Or => PREC_OR,
+            _ => PREC_ASSIGN,
+        }
+    }
+}
+
+
+impl UnOp {
+    /// Returns the operator token.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UnOp::Neg => "-",
+            UnOp::Not => "!",
+            UnOp::Deref => "*",
+            UnOp::Ref => "&",
+            UnOp::RefMut => "&mut ",
+        }
+    }
+}
+
+
+impl Closure {
+    /// Return a new closure with the given body.
+    pub fn new(body: impl Into<Expr>) -> Self {
+        Closure {
+            is_move: false,
+            params: vec![],
+            ret: None,
+            body: body.into(),
+        }
+    }
+
+    /// Set whether the closure captures by move.
+    pub fn set_move(&mut self, is_move: bool) -> &mut Self {
+        self.is_move = is_move;
+        self
+    }
+
+    /// Add a parameter pattern.
+    pub fn param(&mut self, pat: impl Into<String>) -> &mut Self {
+        self.params.push((pat.into(), None));
+        self
+    }
+
+    /// Add a parameter pattern with a type annotation.
+    pub fn typed_param<S, T>(&mut self, pat: S, ty: T) -> &mut Self
+    where
+        S: Into<String>,
+        T: Into<Type>,
+    {
+        self.params.push((pat.into(), Some(ty.into())));
+        self
+    }
+
+    /// Set the return type.
+    ///
+    /// Closures with a return type must have a block body, so the body is
+    /// wrapped in a block if needed.
+    pub fn ret(&mut self, ty: impl Into<Type>) -> &mut Self {
+        self.ret = Some(ty.into());
+        self
+    }
+
+    /// Formats the closure using the given formatter.
+    pub fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        if self.is_move {
+            write!(fmt, "move ")?;
+        }
+
+        write!(fmt, "|")?;
+
+        for (i, (pat, ty)) in self.params.iter().enumerate() {
+            if i != 0 {
+                write!(fmt, ", ")?;
+            }
+
+            write!(fmt, "{}", pat)?;
+
+            if let Some(ty) = ty {
+                write!(fmt, ": ")?;
+                ty.fmt(fmt)?;
+            }
+        }
+
+        write!(fmt, "| ")?;
+
+        match &self.ret {
+            Some(ret) => {
+                write!(fmt, "-> ")?;
+                ret.fmt(fmt)?;
+
+                match &self.body {
+                    Expr::Block(block) => block.fmt_inline(fmt),
+                    body => {
+                        write!(fmt, " {{ ")?;
+                        body.fmt(fmt)?;
+                        write!(fmt, " }}")
+                    }
+                }
+            }
+            None => self.body.fmt(fmt),
+        }
+    }
+}
+
+
+fn collect<I, T>(items: I) -> Vec<Expr>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<Expr>,
+{
+    items.into_iter().map(Into::into).collect()
+}
+
+fn fmt_list(open: &str, exprs: &[Expr], close: &str, fmt: &mut Formatter) -> fmt::Result {
+    write!(fmt, "{}", open)?;
+
+    for (i, expr) in exprs.iter().enumerate() {
+        if i != 0 {
+            write!(fmt, ", ")?;
+        }
+        expr.fmt(fmt)?;
+    }
+
+    write!(fmt, "{}", close)
+}
+
+/// Returns true if the raw source text does not need to be parenthesized
+/// when used as an operand, i.e. it has no top-level whitespace and does not
+/// start with a prefix operator.
+fn is_atomic(src: &str) -> bool {
+    if src.starts_with(['-', '!', '*', '&']) {
+        return false;
+    }
+
+    let mut depth = 0usize;
+    let mut chars = src.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            '"' => {
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            c if c.is_whitespace() && depth == 0 => return false,
+            _ => {}
+        }
+    }
+
+    true
+}
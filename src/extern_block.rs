@@ -0,0 +1,178 @@
+use std::fmt::{self, Write};
+
+use crate::attr::Attr;
+use crate::cfg::Cfg;
+use crate::docs::Docs;
+use crate::formatter::{Formatter, Format};
+use crate::function::Function;
+use crate::var_def::VarDef;
+
+use crate::r#type::Type;
+
+
+/// Defines an `extern` block declaring foreign functions and statics.
+///
+/// ```
+/// use codegen::ExternBlock;
+///
+/// let mut block = ExternBlock::new("C");
+/// block.link("m");
+/// block.new_fn("cos").arg("x", "f64").ret("f64");
+/// ```
+#[derive(Debug, Clone)]
+pub struct ExternBlock {
+    /// ABI of the declared items, e.g. `C`
+    abi: String,
+
+    /// Whether the block is written as `unsafe extern`
+    r#unsafe: bool,
+
+    /// Block documentation
+    docs: Option<Docs>,
+
+    /// Block attributes, e.g. `link(name = "m")`
+    attributes: Vec<Attr>,
+
+    /// Declared items
+    items: Vec<ForeignItem>,
+}
+
+
+#[derive(Debug, Clone)]
+enum ForeignItem {
+    Function(Function),
+    Static(VarDef),
+}
+
+
+impl ExternBlock {
+    /// Return a new `extern` block with the given ABI.
+    pub fn new(abi: impl Into<String>) -> Self {
+        ExternBlock {
+            abi: abi.into(),
+            r#unsafe: false,
+            docs: None,
+            attributes: vec![],
+            items: vec![],
+        }
+    }
+
+    /// Set whether the block is declared as `unsafe extern`, as required by
+    /// the 2024 edition.
+    pub fn set_unsafe(&mut self, r#unsafe: bool) -> &mut Self {
+        self.r#unsafe = r#unsafe;
+        self
+    }
+
+    /// Set the block documentation.
+    pub fn doc(&mut self, docs: impl Into<String>) -> &mut Self {
+        self.docs = Some(Docs::new(docs));
+        self
+    }
+
+    /// Add an attribute to the block, e.g. `"link(name = \"m\")"`.
+    pub fn attr(&mut self, attribute: impl Into<String>) -> &mut Self {
+        self.push_attr(Attr::from(attribute.into()))
+    }
+
+    /// Push an attribute to the block.
+    pub fn push_attr(&mut self, attr: Attr) -> &mut Self {
+        self.attributes.push(attr);
+        self
+    }
+
+    /// Create an attribute on the block.
+    pub fn new_attr(&mut self, name: impl Into<String>) -> &mut Attr {
+        self.push_attr(Attr::new(name));
+
+        self.attributes.last_mut().unwrap()
+    }
+
+    /// Push a `#[cfg(pred)]` attribute.
+    pub fn cfg(&mut self, pred: Cfg) -> &mut Self {
+        self.push_attr(Attr::cfg(pred))
+    }
+
+    /// Push a `#[cfg_attr(pred, attr)]` attribute.
+    pub fn cfg_attr(&mut self, pred: Cfg, attr: Attr) -> &mut Self {
+        self.push_attr(Attr::cfg_attr(pred, attr))
+    }
+
+    /// Link against the given native library, i.e. `#[link(name = "...")]`.
+    pub fn link(&mut self, name: impl AsRef<str>) -> &mut Self {
+        self.new_attr("link").list().name_value("name", name.as_ref());
+        self
+    }
+
+    /// Push a new foreign function declaration, returning a mutable
+    /// reference to it.
+    pub fn new_fn(&mut self, name: impl Into<String>) -> &mut Function {
+        let mut func = Function::new(name);
+        func.body = None;
+        self.push_fn(func);
+
+        match self.items.last_mut().unwrap() {
+            ForeignItem::Function(v) => v,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Push a foreign function declaration. The function must not define a
+    /// body.
+    pub fn push_fn(&mut self, item: Function) -> &mut Self {
+        self.items.push(ForeignItem::Function(item));
+        self
+    }
+
+    /// Push a new foreign static declaration, returning a mutable reference
+    /// to it.
+    pub fn new_static<S, T>(&mut self, name: S, ty: T) -> &mut VarDef
+    where
+        S: Into<String>,
+        T: Into<Type>,
+    {
+        self.push_static(VarDef::new_static(name, ty));
+
+        match self.items.last_mut().unwrap() {
+            ForeignItem::Static(v) => v,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Push a foreign static declaration. The static must not have a value.
+    pub fn push_static(&mut self, item: VarDef) -> &mut Self {
+        self.items.push(ForeignItem::Static(item));
+        self
+    }
+}
+
+
+impl Format for ExternBlock {
+    /// Formats the `extern` block using the given formatter.
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        if let Some(docs) = &self.docs {
+            docs.fmt(fmt, false)?;
+        }
+
+        for attr in &self.attributes {
+            attr.fmt(fmt, false)?;
+        }
+
+        if self.r#unsafe {
+            write!(fmt, "unsafe ")?;
+        }
+
+        write!(fmt, "extern \"{}\"", self.abi)?;
+
+        fmt.block(|fmt| {
+            for item in &self.items {
+                match item {
+                    ForeignItem::Function(v) => v.fmt_foreign(self.r#unsafe, fmt)?,
+                    ForeignItem::Static(v) => v.fmt_foreign(fmt)?,
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
@@ -1,3 +1,7 @@
+use crate::attr::Attr;
+use crate::cfg::Cfg;
+use crate::visibility::Visibility;
+
 use crate::r#type::Type;
 
 
@@ -5,7 +9,7 @@ use crate::r#type::Type;
 #[derive(Debug, Clone)]
 pub struct Field {
     /// visibility
-    pub vis: Option<String>,
+    pub vis: Visibility,
 
     /// Field name
     pub name: String,
@@ -16,8 +20,8 @@ pub struct Field {
     /// Field documentation
     pub documentation: Vec<String>,
 
-    /// Field annotation
-    pub annotation: Vec<String>,
+    /// Field attributes
+    pub attrs: Vec<Attr>,
 }
 
 
@@ -29,17 +33,17 @@ impl Field {
             T: Into<Type>,
     {
         Field {
-            vis: None,
+            vis: Visibility::Private,
             name: name.into(),
             ty: ty.into(),
             documentation: vec![],
-            annotation: vec![],
+            attrs: vec![],
         }
     }
 
     /// Set field's visibility.
-    pub fn vis(&mut self, vis: impl Into<String>) {
-        self.vis = Some(vis.into());
+    pub fn vis(&mut self, vis: impl Into<Visibility>) {
+        self.vis = vis.into();
     }
 
     /// Set field's documentation.
@@ -57,7 +61,8 @@ impl Field {
         self
     }
 
-    /// Set field's annotation.
+    /// Set field's attributes, e.g. `#[serde(default)]`, replacing any
+    /// previous attributes.
     pub fn annotation<II, I, S>(
         &mut self,
         annotation: II,
@@ -67,9 +72,32 @@ impl Field {
             I: Iterator<Item=S>,
             S: Into<String>,
     {
-        self.annotation = annotation.into_iter()
-            .map(|ann| ann.into())
+        self.attrs = annotation.into_iter()
+            .map(|ann| Attr::from(ann.into()))
             .collect();
         self
     }
+
+    /// Push an attribute to the field.
+    pub fn push_attr(&mut self, attr: Attr) -> &mut Self {
+        self.attrs.push(attr);
+        self
+    }
+
+    /// Create an attribute on the field.
+    pub fn new_attr(&mut self, name: impl Into<String>) -> &mut Attr {
+        self.push_attr(Attr::new(name));
+
+        self.attrs.last_mut().unwrap()
+    }
+
+    /// Push a `#[cfg(pred)]` attribute.
+    pub fn cfg(&mut self, pred: Cfg) -> &mut Self {
+        self.push_attr(Attr::cfg(pred))
+    }
+
+    /// Push a `#[cfg_attr(pred, attr)]` attribute.
+    pub fn cfg_attr(&mut self, pred: Cfg, attr: Attr) -> &mut Self {
+        self.push_attr(Attr::cfg_attr(pred, attr))
+    }
 }
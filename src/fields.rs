@@ -65,11 +65,11 @@ impl Format for Fields {
                         for doc in &f.documentation {
                             writeln!(fmt, "/// {}", doc)?;
                         }
-                        for ann in &f.annotation {
-                            writeln!(fmt, "{}", ann)?;
+                        for attr in &f.attrs {
+                            attr.fmt(fmt, false)?;
                         }
-                        if let Some(vis) = &f.vis {
-                            write!(fmt, "{} ", vis)?;
+                        if !f.vis.is_private() {
+                            write!(fmt, "{} ", f.vis)?;
                         }
                         write!(fmt, "{}: ", f.name)?;
                         f.ty.fmt(fmt)?;
@@ -1,6 +1,10 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fmt::{self, Write};
 
-use crate::bound::Bound;
+use crate::attr::Attr;
+use crate::generic_param::GenericParam;
+use crate::import::{ImportOrder, PruneImports};
+use crate::where_predicate::WherePredicate;
 
 use crate::r#type::Type;
 
@@ -26,6 +30,41 @@ pub struct Formatter<'a> {
 
     /// Number of spaces per indentation
     indent: usize,
+
+    /// Import settings of the innermost scope
+    pub(crate) imports: ImportState,
+
+    /// Features referenced by `cfg` and `cfg_attr` attributes written so
+    /// far, collected during a dry run
+    pub(crate) features: Option<BTreeSet<String>>,
+
+    /// Whether the output is discarded, e.g. when collecting the paths a
+    /// scope refers to
+    pub(crate) dry_run: bool,
+}
+
+
+/// Tracks qualified paths for the scope being formatted.
+#[derive(Debug, Default)]
+pub(crate) struct ImportState {
+    /// Whether qualified type paths are imported automatically
+    pub(crate) auto_import: bool,
+
+    /// Order imports are written in
+    pub(crate) order: ImportOrder,
+
+    /// Which references keep imports from being pruned
+    pub(crate) prune: PruneImports,
+
+    /// Qualified paths written so far, collected during a dry run
+    pub(crate) seen: Option<Vec<String>>,
+
+    /// Names that imported qualified paths are written as
+    pub(crate) names: HashMap<String, String>,
+
+    /// First segments of the paths written so far, collected during a dry
+    /// run
+    pub(crate) used: Option<HashSet<String>>,
 }
 
 
@@ -36,6 +75,76 @@ impl<'a> Formatter<'a> {
             dst,
             spaces: 0,
             indent: DEFAULT_INDENT,
+            imports: ImportState::default(),
+            features: None,
+            dry_run: false,
+        }
+    }
+
+    /// Return a formatter writing to `dst` with the same indentation as
+    /// this one, for formatting a dry run.
+    pub(crate) fn scratch<'b>(&self, dst: &'b mut String) -> Formatter<'b> {
+        Formatter {
+            dst,
+            spaces: 0,
+            indent: self.indent,
+            imports: ImportState::default(),
+            features: None,
+            dry_run: true,
+        }
+    }
+
+    /// Write a type path, replacing it with the name it was imported as.
+    ///
+    /// During a dry run, qualified paths are recorded instead.
+    pub(crate) fn write_type_path(&mut self, path: &str) -> fmt::Result {
+        if let Some(seen) = &mut self.imports.seen {
+            if is_importable(path) && !seen.iter().any(|p| p == path) {
+                seen.push(path.to_string());
+            }
+        }
+
+        match self.imports.names.get(path) {
+            Some(name) => {
+                let name = name.clone();
+                self.write_path(&name)
+            }
+            None => self.write_path(path),
+        }
+    }
+
+    /// Records the paths named by an attribute, e.g. the derived traits, and
+    /// the features referenced by a `cfg` or `cfg_attr` attribute during a
+    /// dry run.
+    pub(crate) fn record_attr(&mut self, attr: &Attr) {
+        if self.imports.used.is_some() {
+            for path in attr.paths() {
+                self.record_path(path);
+            }
+        }
+
+        if let Some(features) = &mut self.features {
+            if let Some(pred) = attr.cfg_predicate() {
+                features.extend(pred.features().into_iter().map(String::from));
+            }
+        }
+    }
+
+    /// Write a path, e.g. `fmt::Display` or `Some`.
+    ///
+    /// During a dry run, the first segment of the path is recorded as used.
+    pub(crate) fn write_path(&mut self, path: &str) -> fmt::Result {
+        self.record_path(path);
+        write!(self, "{}", path)
+    }
+
+    /// Records the first segment of the path as used during a dry run.
+    pub(crate) fn record_path(&mut self, path: &str) {
+        if let Some(used) = &mut self.imports.used {
+            if !path.starts_with("::") {
+                let first = path.split("::").next().unwrap();
+                used.insert(first.to_string());
+            }
         }
     }
 
@@ -74,6 +183,15 @@ impl<'a> Formatter<'a> {
         self.dst.is_empty() || self.dst.as_bytes().last() == Some(&b'\n')
     }
 
+    /// Check if a space is needed before writing a token that must be
+    /// separated from the preceding text.
+    pub(crate) fn needs_space(&self) -> bool {
+        match self.dst.chars().last() {
+            None => false,
+            Some(c) => !c.is_whitespace() && c != '(' && c != '[',
+        }
+    }
+
     fn push_spaces(&mut self) -> fmt::Result {
         write!(self.dst, "{:1$}", "", self.spaces)
     }
@@ -86,7 +204,7 @@ impl<'a> fmt::Write for Formatter<'a> {
 
         for line in s.lines() {
             if !first {
-                self.dst.push_str("\n");
+                self.dst.push('\n');
             }
 
             first = false;
@@ -104,7 +222,7 @@ impl<'a> fmt::Write for Formatter<'a> {
         }
 
         if let Some(b'\n') = s.as_bytes().last() {
-            self.dst.push_str("\n");
+            self.dst.push('\n');
         }
 
         Ok(())
@@ -112,16 +230,34 @@ impl<'a> fmt::Write for Formatter<'a> {
 }
 
 
-/// Format generics.
-pub fn fmt_generics(generics: &[String], fmt: &mut Formatter) -> fmt::Result {
+/// Returns true if the path names an item in a module, e.g.
+/// `std::fmt::Display`, as opposed to an associated item like `T::Output`.
+fn is_importable(path: &str) -> bool {
+    let path = path.trim_start_matches("::");
+
+    if !path.chars().all(|c| c == '_' || c == ':' || c.is_alphanumeric()) {
+        return false;
+    }
+
+    match path.split_once("::") {
+        Some((first, _)) => first.starts_with(|c: char| c == '_' || c.is_lowercase()),
+        None => false,
+    }
+}
+
+
+/// Format generic parameter declarations.
+pub fn fmt_generics(generics: &[GenericParam], fmt: &mut Formatter) -> fmt::Result {
     if !generics.is_empty() {
         write!(fmt, "<")?;
 
-        for (i, ty) in generics.iter().enumerate() {
+        for (i, param) in generics.iter().enumerate() {
             if i != 0 {
                 write!(fmt, ", ")?
             }
-            write!(fmt, "{}", ty)?;
+            // The parameter shadows any import of the same name
+            fmt.record_path(param.name());
+            param.fmt(fmt)?;
         }
 
         write!(fmt, ">")?;
@@ -130,19 +266,19 @@ pub fn fmt_generics(generics: &[String], fmt: &mut Formatter) -> fmt::Result {
     Ok(())
 }
 
-/// Format generic bounds.
-pub fn fmt_bounds(bounds: &[Bound], fmt: &mut Formatter) -> fmt::Result {
+/// Format a `where` clause.
+pub fn fmt_bounds(bounds: &[WherePredicate], fmt: &mut Formatter) -> fmt::Result {
     if !bounds.is_empty() {
         writeln!(fmt)?;
 
-        // Write first bound
-        write!(fmt, "where {}: ", bounds[0].name)?;
-        fmt_bound_rhs(&bounds[0].bound, fmt)?;
+        // Write first predicate
+        write!(fmt, "where ")?;
+        bounds[0].fmt(fmt)?;
         writeln!(fmt, ",")?;
 
         for bound in &bounds[1..] {
-            write!(fmt, "      {}: ", bound.name)?;
-            fmt_bound_rhs(&bound.bound, fmt)?;
+            write!(fmt, "      ")?;
+            bound.fmt(fmt)?;
             writeln!(fmt, ",")?;
         }
     }
@@ -161,3 +297,45 @@ pub fn fmt_bound_rhs(tys: &[Type], fmt: &mut Formatter) -> fmt::Result {
 
     Ok(())
 }
+
+
+/// Splits the text into lines, dropping surrounding blank lines and the
+/// indentation common to all lines.
+pub(crate) fn dedent(src: &str) -> Vec<String> {
+    let lines: Vec<&str> = src
+        .lines()
+        .skip_while(|line| line.trim().is_empty())
+        .collect();
+
+    let end = lines
+        .iter()
+        .rposition(|line| !line.trim().is_empty())
+        .map_or(0, |i| i + 1);
+
+    let lines = &lines[..end];
+
+    // The leading whitespace shared by all lines, compared char by char so
+    // that tabs and spaces are not mixed up
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| &line[..line.len() - line.trim_start().len()])
+        .reduce(|indent, ws| {
+            let len = indent
+                .char_indices()
+                .zip(ws.chars())
+                .find(|((_, a), b)| a != b)
+                .map_or(indent.len().min(ws.len()), |((i, _), _)| i);
+
+            &indent[..len]
+        })
+        .unwrap_or("");
+
+    lines
+        .iter()
+        .map(|line| match line.strip_prefix(indent) {
+            Some(line) if !line.trim().is_empty() => line.trim_end().to_string(),
+            _ => String::new(),
+        })
+        .collect()
+}
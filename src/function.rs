@@ -1,12 +1,19 @@
 use std::fmt::{self, Write};
 
+use crate::attr::{Attr, set_attr};
+use crate::cfg::Cfg;
+use crate::attr_arg::AttrArg;
 use crate::block::Block;
 use crate::body::Body;
-use crate::bound::Bound;
 use crate::docs::Docs;
+use crate::expr::Expr;
 use crate::formatter::{fmt_bounds, fmt_generics};
 use crate::formatter::{Formatter, Format};
+use crate::generic_param::{GenericParam, push_generic};
 use crate::param::Param;
+use crate::stmt::{Let, Stmt};
+use crate::visibility::Visibility;
+use crate::where_predicate::{WherePredicate, push_predicate};
 
 use crate::r#type::Type;
 
@@ -20,14 +27,11 @@ pub struct Function {
     /// Function documentation
     docs: Option<Docs>,
 
-    /// A lint attribute used to suppress a warning or error
-    allow: Option<String>,
-
     /// Function visibility
-    vis: Option<String>,
+    vis: Visibility,
 
     /// Function generics
-    generics: Vec<String>,
+    generics: Vec<GenericParam>,
 
     /// If the function takes `&self` or `&mut self`
     param_self: Option<String>,
@@ -35,23 +39,32 @@ pub struct Function {
     /// Function arguments
     params: Vec<Param>,
 
+    /// Whether the function takes C variadic arguments, i.e. `...`
+    variadic: bool,
+
     /// Return type
     ret: Option<Type>,
 
     /// Where bounds
-    bounds: Vec<Bound>,
+    bounds: Vec<WherePredicate>,
 
     /// Body contents
     pub body: Option<Vec<Body>>,
 
     /// Function attributes, e.g., `#[no_mangle]`.
-    attributes: Vec<String>,
+    attrs: Vec<Attr>,
 
     /// Function `extern` ABI
     extern_abi: Option<String>,
 
     /// Whether or not this function is `async` or not
     r#async: bool,
+
+    /// Whether the function is a `const fn`
+    r#const: bool,
+
+    /// Whether the function is an `unsafe fn`
+    r#unsafe: bool,
 }
 
 
@@ -61,17 +74,19 @@ impl Function {
         Function {
             name: name.into(),
             docs: None,
-            allow: None,
-            vis: None,
+            vis: Visibility::Private,
             generics: vec![],
             param_self: None,
             params: vec![],
+            variadic: false,
             ret: None,
             bounds: vec![],
             body: Some(vec![]),
-            attributes: vec![],
+            attrs: vec![],
             extern_abi: None,
             r#async: false,
+            r#const: false,
+            r#unsafe: false,
         }
     }
 
@@ -83,13 +98,23 @@ impl Function {
 
     /// Specify lint attribute to supress a warning or error.
     pub fn allow(&mut self, allow: impl Into<String>) -> &mut Self {
-        self.allow = Some(allow.into());
+        set_attr(&mut self.attrs, "allow", AttrArg::delimited(allow));
         self
     }
 
+    /// Returns the function name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the function visibility.
+    pub fn visibility(&self) -> &Visibility {
+        &self.vis
+    }
+
     /// Set the function visibility.
-    pub fn vis(&mut self, vis: impl Into<String>) -> &mut Self {
-        self.vis = Some(vis.into());
+    pub fn vis(&mut self, vis: impl Into<Visibility>) -> &mut Self {
+        self.vis = vis.into();
         self
     }
 
@@ -99,9 +124,23 @@ impl Function {
         self
     }
 
-    /// Add a generic to the function.
-    pub fn generic(&mut self, name: impl Into<String>) -> &mut Self {
-        self.generics.push(name.into());
+    /// Set whether this function is a `const fn`.
+    ///
+    /// Const fns cannot be `async` or declared in traits.
+    pub fn set_const(&mut self, r#const: bool) -> &mut Self {
+        self.r#const = r#const;
+        self
+    }
+
+    /// Set whether this function is an `unsafe fn`.
+    pub fn set_unsafe(&mut self, r#unsafe: bool) -> &mut Self {
+        self.r#unsafe = r#unsafe;
+        self
+    }
+
+    /// Add a generic parameter to the function.
+    pub fn generic(&mut self, param: impl Into<GenericParam>) -> &mut Self {
+        push_generic(&mut self.generics, param.into());
         self
     }
 
@@ -133,6 +172,7 @@ impl Function {
             name: name.into(),
             ty: ty.into(),
             modi: None,
+            attrs: vec![],
         });
 
         self
@@ -148,11 +188,19 @@ impl Function {
             name: name.into(),
             ty: ty.into(),
             modi: Some("mut".to_owned()),
+            attrs: vec![],
         });
 
         self
     }
 
+    /// Set whether the function takes C variadic arguments, written as a
+    /// trailing `...`. Only foreign functions may be variadic.
+    pub fn set_variadic(&mut self, variadic: bool) -> &mut Self {
+        self.variadic = variadic;
+        self
+    }
+
     /// Set the function return type.
     pub fn ret<T>(&mut self, ty: T) -> &mut Self
     where
@@ -165,16 +213,31 @@ impl Function {
     /// Add a `where` bound to the function.
     pub fn bound<S, T>(&mut self, name: S, ty: T) -> &mut Self
     where
-        S: Into<String>,
+        S: Into<Type>,
         T: Into<Type>,
     {
-        self.bounds.push(Bound {
-            name: name.into(),
-            bound: vec![ty.into()],
-        });
+        self.new_predicate(name).bound(ty);
+        self
+    }
+
+    /// Push a `where` predicate to the function.
+    ///
+    /// Bounds on a type that is already constrained are merged into the
+    /// existing predicate.
+    pub fn push_predicate(&mut self, pred: WherePredicate) -> &mut Self {
+        push_predicate(&mut self.bounds, pred);
         self
     }
 
+    /// Returns the `where` predicate on the given type, creating it if it
+    /// does not exist.
+    pub fn new_predicate<T>(&mut self, bounded: T) -> &mut WherePredicate
+    where
+        T: Into<Type>,
+    {
+        push_predicate(&mut self.bounds, WherePredicate::new(bounded))
+    }
+
     /// Push a line to the function implementation.
     pub fn line<T>(&mut self, line: T) -> &mut Self
     where
@@ -187,6 +250,32 @@ impl Function {
         self
     }
 
+    /// Push a statement to the function implementation.
+    pub fn push_stmt(&mut self, stmt: impl Into<Stmt>) -> &mut Self {
+        self.body
+            .get_or_insert(vec![])
+            .push(Body::Stmt(stmt.into()));
+
+        self
+    }
+
+    /// Push an expression without a trailing semicolon to the function
+    /// implementation.
+    pub fn push_expr(&mut self, expr: impl Into<Expr>) -> &mut Self {
+        self.push_stmt(Stmt::Expr(expr.into()))
+    }
+
+    /// Push a new `let` binding to the function implementation, returning a
+    /// mutable reference to it.
+    pub fn new_let(&mut self, pat: impl Into<String>) -> &mut Let {
+        self.push_stmt(Let::new(pat));
+
+        match self.body.as_mut().unwrap().last_mut().unwrap() {
+            Body::Stmt(Stmt::Let(v)) => v,
+            _ => unreachable!(),
+        }
+    }
+
     /// Add an attribute to the function.
     ///
     /// ```
@@ -198,7 +287,45 @@ impl Function {
     /// func.attr("test");
     /// ```
     pub fn attr(&mut self, attribute: impl Into<String>) -> &mut Self {
-        self.attributes.push(attribute.into());
+        self.push_attr(Attr::from(attribute.into()))
+    }
+
+    /// Push an attribute to the function.
+    pub fn push_attr(&mut self, attr: Attr) -> &mut Self {
+        self.attrs.push(attr);
+        self
+    }
+
+    /// Create an attribute on the function.
+    pub fn new_attr(&mut self, name: impl Into<String>) -> &mut Attr {
+        self.push_attr(Attr::new(name));
+
+        self.attrs.last_mut().unwrap()
+    }
+
+    /// Push a `#[cfg(pred)]` attribute.
+    pub fn cfg(&mut self, pred: Cfg) -> &mut Self {
+        self.push_attr(Attr::cfg(pred))
+    }
+
+    /// Push a `#[cfg_attr(pred, attr)]` attribute.
+    pub fn cfg_attr(&mut self, pred: Cfg, attr: Attr) -> &mut Self {
+        self.push_attr(Attr::cfg_attr(pred, attr))
+    }
+
+    /// Push an attribute to the last argument added, e.g.
+    /// `#[cfg(unix)] fd: RawFd`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the function has no arguments.
+    pub fn arg_attr(&mut self, attr: Attr) -> &mut Self {
+        self.params
+            .last_mut()
+            .expect("no argument to add the attribute to")
+            .attrs
+            .push(attr);
+
         self
     }
 
@@ -225,37 +352,91 @@ impl Function {
 
     /// Formats the function using the given formatter.
     pub fn fmt(&self, is_trait: bool, fmt: &mut Formatter) -> fmt::Result {
-        if let Some(ref docs) = self.docs {
-            docs.fmt(fmt, false)?;
+        assert!(!self.variadic, "only foreign fns can be variadic");
+
+        self.fmt_head(is_trait, fmt)?;
+
+        match self.body {
+            Some(ref body) => fmt.block(|fmt| {
+                for b in body {
+                    b.fmt(fmt)?;
+                }
+
+                Ok(())
+            }),
+            None => {
+                if !is_trait {
+                    panic!("impl blocks must define fn bodies");
+                }
+
+                write!(fmt, ";")
+            }
         }
+    }
+
+    /// Formats the function as a body-less declaration in an `extern` block,
+    /// which may be an `unsafe extern` block.
+    pub(crate) fn fmt_foreign(&self, unsafe_block: bool, fmt: &mut Formatter) -> fmt::Result {
+        assert!(
+            !matches!(&self.body, Some(body) if !body.is_empty()),
+            "foreign fns cannot define a body"
+        );
+        assert!(self.extern_abi.is_none(), "foreign fns cannot set an ABI");
+        assert!(!self.r#async, "foreign fns cannot be async");
+        assert!(!self.r#const, "foreign fns cannot be const");
+        assert!(
+            !self.r#unsafe || unsafe_block,
+            "foreign fns can only be unsafe in an `unsafe extern` block"
+        );
+        assert!(
+            self.generics.iter().all(|param| param.is_lifetime()),
+            "foreign fns cannot have type or const parameters"
+        );
+
+        self.fmt_head(false, fmt)?;
+        writeln!(fmt, ";")
+    }
 
-        if let Some(ref allow) = self.allow {
-            writeln!(fmt, "#[allow({})]", allow)?;
+    /// Formats everything up to the function body.
+    fn fmt_head(&self, is_trait: bool, fmt: &mut Formatter) -> fmt::Result {
+        if let Some(ref docs) = self.docs {
+            docs.fmt(fmt, false)?;
         }
 
-        for attr in self.attributes.iter() {
-            writeln!(fmt, "#[{}]", attr)?;
+        for attr in &self.attrs {
+            attr.fmt(fmt, false)?;
         }
 
         if is_trait {
             assert!(
-                self.vis.is_none(),
+                self.vis.is_private(),
                 "trait fns do not have visibility modifiers"
             );
         }
 
-        if let Some(ref vis) = self.vis {
-            write!(fmt, "{} ", vis)?;
+        if !self.vis.is_private() {
+            write!(fmt, "{} ", self.vis)?;
         }
 
-        if let Some(ref extern_abi) = self.extern_abi {
-            write!(fmt, "extern \"{extern_abi}\" ", extern_abi = extern_abi)?;
+        if self.r#const {
+            assert!(!self.r#async, "const fns cannot be async");
+            assert!(!is_trait, "trait fns cannot be const");
+
+            write!(fmt, "const ")?;
         }
 
         if self.r#async {
             write!(fmt, "async ")?;
         }
 
+        if self.r#unsafe {
+            write!(fmt, "unsafe ")?;
+        }
+
+        if let Some(ref extern_abi) = self.extern_abi {
+            write!(fmt, "extern \"{extern_abi}\" ", extern_abi = extern_abi)?;
+        }
+
         write!(fmt, "fn {}", self.name)?;
         fmt_generics(&self.generics, fmt)?;
 
@@ -272,6 +453,11 @@ impl Function {
             }
             comma = true;
 
+            for attr in &param.attrs {
+                attr.fmt_inline(fmt)?;
+                write!(fmt, " ")?;
+            }
+
             if let Some(modi) = &param.modi {
                 write!(fmt, "{} ", modi)?;
             }
@@ -279,6 +465,14 @@ impl Function {
             param.ty.fmt(fmt)?;
         }
 
+        if self.variadic {
+            if comma {
+                write!(fmt, ", ")?;
+            }
+
+            write!(fmt, "...")?;
+        }
+
         write!(fmt, ")")?;
 
         if let Some(ref ret) = self.ret {
@@ -286,23 +480,6 @@ impl Function {
             ret.fmt(fmt)?;
         }
 
-        fmt_bounds(&self.bounds, fmt)?;
-
-        match self.body {
-            Some(ref body) => fmt.block(|fmt| {
-                for b in body {
-                    b.fmt(fmt)?;
-                }
-
-                Ok(())
-            }),
-            None => {
-                if !is_trait {
-                    panic!("impl blocks must define fn bodies");
-                }
-
-                writeln!(fmt, ";")
-            }
-        }
+        fmt_bounds(&self.bounds, fmt)
     }
 }
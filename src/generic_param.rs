@@ -0,0 +1,289 @@
+use std::fmt::{self, Write};
+
+use crate::formatter::{Formatter, fmt_bound_rhs};
+use crate::parse::{ParseError, Parser};
+
+use crate::r#type::Type;
+
+
+/// Defines a generic parameter, e.g. `'a`, `T: Clone = String` or
+/// `const N: usize`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenericParam {
+    kind: Kind,
+    name: String,
+    bounds: Vec<Type>,
+    default: Option<Type>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Kind {
+    Lifetime,
+    Type,
+    Const(Type),
+    /// A declaration that could not be parsed, rendered as is. The name is
+    /// the leading identifier or lifetime of the declaration.
+    Verbatim(String),
+}
+
+
+impl GenericParam {
+    /// Return a new type parameter with the given name.
+    pub fn new(name: impl Into<String>) -> Self {
+        GenericParam::with_kind(Kind::Type, name.into())
+    }
+
+    /// Return a new lifetime parameter. The leading `'` is optional.
+    pub fn new_lifetime(name: impl Into<String>) -> Self {
+        let mut name = name.into();
+
+        if !name.starts_with('\'') {
+            name.insert(0, '\'');
+        }
+
+        GenericParam::with_kind(Kind::Lifetime, name)
+    }
+
+    /// Return a new const parameter of the given type.
+    pub fn new_const<S, T>(name: S, ty: T) -> Self
+    where
+        S: Into<String>,
+        T: Into<Type>,
+    {
+        GenericParam::with_kind(Kind::Const(ty.into()), name.into())
+    }
+
+    /// Return a parameter of the unparsed declaration, named after its
+    /// leading identifier or lifetime.
+    fn verbatim(src: String) -> Self {
+        let mut parser = Parser::new(&src);
+        parser.eat_keyword("const");
+
+        let name = parser
+            .lifetime()
+            .or_else(|| parser.ident())
+            .unwrap_or_else(|| src.clone());
+
+        GenericParam::with_kind(Kind::Verbatim(src), name)
+    }
+
+    fn with_kind(kind: Kind, name: String) -> Self {
+        GenericParam {
+            kind,
+            name,
+            bounds: vec![],
+            default: None,
+        }
+    }
+
+    /// Parse a generic parameter declaration, e.g. `T: Clone = String`.
+    pub fn parse(src: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser::new(src);
+
+        let param = if let Some(lifetime) = parser.lifetime() {
+            let mut param = GenericParam::new_lifetime(lifetime);
+
+            if parser.eat(":") {
+                param.bounds = parser.parse_bounds()?;
+            }
+
+            param
+        } else if parser.eat_keyword("const") {
+            let name = match parser.ident() {
+                Some(name) => name,
+                None => return parser.error("expected a parameter name"),
+            };
+
+            parser.expect(":")?;
+
+            let mut param = GenericParam::new_const(name, parser.parse_type()?);
+
+            if parser.eat("=") {
+                param.default = Some(Type::from(parser.raw_until_close()?));
+            }
+
+            param
+        } else {
+            let mut param = match parser.ident() {
+                Some(name) => GenericParam::new(name),
+                None => return parser.error("expected a parameter name"),
+            };
+
+            if parser.eat(":") {
+                param.bounds = parser.parse_bounds()?;
+            }
+
+            if parser.eat("=") {
+                param.default = Some(parser.parse_type()?);
+            }
+
+            param
+        };
+
+        parser.finish()?;
+        Ok(param)
+    }
+
+    /// Returns the parameter name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns true if this is a lifetime parameter.
+    pub fn is_lifetime(&self) -> bool {
+        match self.kind {
+            Kind::Lifetime => true,
+            Kind::Verbatim(_) => self.name.starts_with('\''),
+            _ => false,
+        }
+    }
+
+    /// Add a bound to the parameter.
+    ///
+    /// For lifetime parameters the bound must itself be a lifetime.
+    ///
+    /// # Panics
+    ///
+    /// Const parameters cannot have bounds.
+    pub fn bound<T>(&mut self, ty: T) -> &mut Self
+    where
+        T: Into<Type>,
+    {
+        let ty = ty.into();
+
+        match self.kind {
+            Kind::Const(_) => panic!("const parameters cannot have bounds"),
+            Kind::Verbatim(_) => panic!("unparsed parameters cannot have bounds"),
+            Kind::Lifetime => match ty {
+                Type::Lifetime(_) => {}
+                _ => panic!("lifetime parameters can only be bound by lifetimes"),
+            },
+            Kind::Type => {}
+        }
+
+        self.bounds.push(ty);
+        self
+    }
+
+    /// Set the default value of the parameter.
+    ///
+    /// # Panics
+    ///
+    /// Lifetime parameters cannot have defaults.
+    pub fn default<T>(&mut self, ty: T) -> &mut Self
+    where
+        T: Into<Type>,
+    {
+        assert!(
+            self.kind == Kind::Type || matches!(self.kind, Kind::Const(_)),
+            "only type and const parameters can have defaults"
+        );
+
+        self.default = Some(ty.into());
+        self
+    }
+
+    /// Returns the parameter as it is written at the use site, e.g. `T` for
+    /// `T: Clone = String`.
+    pub fn to_type(&self) -> Type {
+        if self.is_lifetime() {
+            Type::Lifetime(self.name.clone())
+        } else {
+            Type::new(self.name.clone())
+        }
+    }
+
+    /// Formats the parameter declaration using the given formatter.
+    pub fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        if let Kind::Verbatim(src) = &self.kind {
+            return write!(fmt, "{}", src);
+        }
+
+        if let Kind::Const(_) = self.kind {
+            write!(fmt, "const ")?;
+        }
+
+        write!(fmt, "{}", self.name)?;
+
+        if let Kind::Const(ty) = &self.kind {
+            write!(fmt, ": ")?;
+            ty.fmt(fmt)?;
+        }
+
+        if !self.bounds.is_empty() {
+            write!(fmt, ": ")?;
+            fmt_bound_rhs(&self.bounds, fmt)?;
+        }
+
+        if let Some(default) = &self.default {
+            write!(fmt, " = ")?;
+            default.fmt(fmt)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses the string with `GenericParam::parse`, falling back to the
+/// verbatim string if it cannot be parsed.
+impl<'a> From<&'a str> for GenericParam {
+    fn from(src: &'a str) -> Self {
+        GenericParam::parse(src)
+            .unwrap_or_else(|_| GenericParam::verbatim(src.to_string()))
+    }
+}
+
+impl From<String> for GenericParam {
+    fn from(src: String) -> Self {
+        GenericParam::from(&src[..])
+    }
+}
+
+impl<'a> From<&'a String> for GenericParam {
+    fn from(src: &'a String) -> Self {
+        GenericParam::from(&src[..])
+    }
+}
+
+impl From<Type> for GenericParam {
+    fn from(src: Type) -> Self {
+        match src {
+            Type::Lifetime(name) => GenericParam::new_lifetime(name),
+            Type::Path { ref name, ref generics } if generics.is_empty() => {
+                GenericParam::from(&name[..])
+            }
+            _ => {
+                let mut name = String::new();
+                src.fmt(&mut Formatter::new(&mut name)).unwrap();
+                GenericParam::verbatim(name)
+            }
+        }
+    }
+}
+
+impl<'a> From<&'a GenericParam> for GenericParam {
+    fn from(src: &'a GenericParam) -> Self {
+        src.clone()
+    }
+}
+
+
+/// Push a generic parameter, keeping lifetime parameters ahead of the others
+/// and parameters with defaults behind them.
+///
+/// Returns the index at which the parameter was inserted.
+pub(crate) fn push_generic(
+    generics: &mut Vec<GenericParam>,
+    param: GenericParam,
+) -> usize {
+    let idx = if param.is_lifetime() {
+        generics.iter().take_while(|p| p.is_lifetime()).count()
+    } else if param.default.is_none() {
+        generics.iter().take_while(|p| p.default.is_none()).count()
+    } else {
+        generics.len()
+    };
+
+    generics.insert(idx, param);
+    idx
+}
@@ -1,9 +1,14 @@
 use std::fmt::{self, Write};
 
-use crate::bound::Bound;
+use crate::attr::Attr;
+use crate::cfg::Cfg;
+use crate::docs::Docs;
 use crate::formatter::{Formatter, Format, fmt_bounds, fmt_generics};
 use crate::function::Function;
+use crate::generic_param::{GenericParam, push_generic};
 use crate::name_ty_pair::NameTypePair;
+use crate::var_def::VarDef;
+use crate::where_predicate::{WherePredicate, push_predicate};
 
 use crate::r#type::Type;
 
@@ -15,7 +20,7 @@ pub struct Impl {
     target: Type,
 
     /// Impl level generics
-    generics: Vec<String>,
+    generics: Vec<GenericParam>,
 
     /// If implementing a trait
     impl_trait: Option<Type>,
@@ -24,11 +29,27 @@ pub struct Impl {
     assoc_tys: Vec<NameTypePair>,
 
     /// Bounds
-    bounds: Vec<Bound>,
+    bounds: Vec<WherePredicate>,
+
+    /// Associated constants
+    consts: Vec<VarDef>,
 
     fns: Vec<Function>,
 
-    macros: Vec<String>,
+    /// Impl documentation
+    docs: Option<Docs>,
+
+    /// Outer attributes
+    attrs: Vec<Attr>,
+
+    /// Whether the impl is an `unsafe impl`
+    r#unsafe: bool,
+
+    /// Whether the impl is a negative impl, e.g. `impl !Sync for Foo`
+    negative: bool,
+
+    /// Whether the trait is implemented as `const`
+    r#const: bool,
 }
 
 
@@ -44,16 +65,21 @@ impl Impl {
             impl_trait: None,
             assoc_tys: vec![],
             bounds: vec![],
+            consts: vec![],
             fns: vec![],
-            macros: vec![],
+            docs: None,
+            attrs: vec![],
+            r#unsafe: false,
+            negative: false,
+            r#const: false,
         }
     }
 
     /// Add a generic to the impl block.
     ///
     /// This adds the generic for the block (`impl<T>`) and not the target type.
-    pub fn generic(&mut self, name: impl Into<String>) -> &mut Self {
-        self.generics.push(name.into());
+    pub fn generic(&mut self, param: impl Into<GenericParam>) -> &mut Self {
+        push_generic(&mut self.generics, param.into());
         self
     }
 
@@ -77,7 +103,57 @@ impl Impl {
 
     /// Add a macro to the impl block (e.g. `"#[async_trait]"`)
     pub fn r#macro(&mut self, r#macro: impl Into<String>) -> &mut Self {
-        self.macros.push(r#macro.into());
+        self.push_attr(Attr::from(r#macro.into()))
+    }
+
+    /// Set the impl block documentation.
+    pub fn doc(&mut self, docs: impl Into<String>) -> &mut Self {
+        self.docs = Some(Docs::new(docs));
+        self
+    }
+
+    /// Push an outer attribute, e.g. `#[cfg(feature = "x")]`.
+    pub fn push_attr(&mut self, attr: Attr) -> &mut Self {
+        self.attrs.push(attr);
+        self
+    }
+
+    /// Create an outer attribute.
+    pub fn new_attr(&mut self, name: impl Into<String>) -> &mut Attr {
+        self.push_attr(Attr::new(name));
+
+        self.attrs.last_mut().unwrap()
+    }
+
+    /// Push a `#[cfg(pred)]` attribute.
+    pub fn cfg(&mut self, pred: Cfg) -> &mut Self {
+        self.push_attr(Attr::cfg(pred))
+    }
+
+    /// Push a `#[cfg_attr(pred, attr)]` attribute.
+    pub fn cfg_attr(&mut self, pred: Cfg, attr: Attr) -> &mut Self {
+        self.push_attr(Attr::cfg_attr(pred, attr))
+    }
+
+    /// Set whether the impl block is an `unsafe impl`, e.g. for `Send`.
+    pub fn set_unsafe(&mut self, r#unsafe: bool) -> &mut Self {
+        self.r#unsafe = r#unsafe;
+        self
+    }
+
+    /// Set whether the impl block is a negative impl, e.g.
+    /// `impl !Sync for Foo {}`.
+    ///
+    /// Negative impls must implement a trait and cannot have items.
+    pub fn negative(&mut self, negative: bool) -> &mut Self {
+        self.negative = negative;
+        self
+    }
+
+    /// Set whether the trait is implemented as `const`, i.e.
+    /// `impl const Trait for Foo`.
+    pub fn set_const(&mut self, r#const: bool) -> &mut Self {
+        self.r#const = r#const;
         self
     }
 
@@ -97,14 +173,49 @@ impl Impl {
 
     /// Add a `where` bound to the impl block.
     pub fn bound<S, T>(&mut self, name: S, ty: T) -> &mut Self
+    where
+        S: Into<Type>,
+        T: Into<Type>,
+    {
+        self.new_predicate(name).bound(ty);
+        self
+    }
+
+    /// Push a `where` predicate to the impl block.
+    ///
+    /// Bounds on a type that is already constrained are merged into the
+    /// existing predicate.
+    pub fn push_predicate(&mut self, pred: WherePredicate) -> &mut Self {
+        push_predicate(&mut self.bounds, pred);
+        self
+    }
+
+    /// Returns the `where` predicate on the given type, creating it if it
+    /// does not exist.
+    pub fn new_predicate<T>(&mut self, bounded: T) -> &mut WherePredicate
+    where
+        T: Into<Type>,
+    {
+        push_predicate(&mut self.bounds, WherePredicate::new(bounded))
+    }
+
+    /// Push a new associated constant, returning a mutable reference to it.
+    pub fn new_const<S, T, V>(&mut self, name: S, ty: T, value: V) -> &mut VarDef
     where
         S: Into<String>,
         T: Into<Type>,
+        V: Into<String>,
     {
-        self.bounds.push(Bound {
-            name: name.into(),
-            bound: vec![ty.into()],
-        });
+        let mut var = VarDef::new_const(name, ty);
+        var.value(value);
+
+        self.push_const(var);
+        self.consts.last_mut().unwrap()
+    }
+
+    /// Push an associated constant, which must have a value.
+    pub fn push_const(&mut self, item: VarDef) -> &mut Self {
+        self.consts.push(item);
         self
     }
 
@@ -125,14 +236,45 @@ impl Impl {
 impl Format for Impl {
     /// Formats the impl block using the given formatter.
     fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
-        for m in self.macros.iter() {
-            writeln!(fmt, "{}", m)?;
+        let is_empty = self.assoc_tys.is_empty() && self.consts.is_empty() && self.fns.is_empty();
+
+        if self.negative {
+            assert!(self.impl_trait.is_some(), "negative impls must implement a trait");
+            assert!(is_empty, "negative impls cannot have items");
+            assert!(!self.r#unsafe, "negative impls cannot be unsafe");
+            assert!(!self.r#const, "negative impls cannot be const");
+        }
+
+        if self.r#const {
+            assert!(self.impl_trait.is_some(), "const impls must implement a trait");
+        }
+
+        if let Some(ref docs) = self.docs {
+            docs.fmt(fmt, false)?;
+        }
+
+        for attr in &self.attrs {
+            attr.fmt(fmt, false)?;
         }
+
+        if self.r#unsafe {
+            write!(fmt, "unsafe ")?;
+        }
+
         write!(fmt, "impl")?;
         fmt_generics(&self.generics[..], fmt)?;
 
         if let Some(ref t) = self.impl_trait {
             write!(fmt, " ")?;
+
+            if self.r#const {
+                write!(fmt, "const ")?;
+            }
+
+            if self.negative {
+                write!(fmt, "!")?;
+            }
+
             t.fmt(fmt)?;
             write!(fmt, " for")?;
         }
@@ -142,6 +284,14 @@ impl Format for Impl {
 
         fmt_bounds(&self.bounds, fmt)?;
 
+        if self.negative {
+            if self.bounds.is_empty() {
+                write!(fmt, " ")?;
+            }
+
+            return write!(fmt, "{{}}");
+        }
+
         fmt.block(|fmt| {
             // format associated types
             for ty in &self.assoc_tys {
@@ -150,7 +300,16 @@ impl Format for Impl {
                 writeln!(fmt, ";")?;
             }
 
-            let mut newline = !self.assoc_tys.is_empty();
+            if !self.assoc_tys.is_empty() && !self.consts.is_empty() {
+                writeln!(fmt)?;
+            }
+
+            // format associated constants
+            for var in &self.consts {
+                var.fmt_assoc(false, fmt)?;
+            }
+
+            let mut newline = !self.assoc_tys.is_empty() || !self.consts.is_empty();
 
             for func in &self.fns {
                 if newline {
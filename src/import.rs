@@ -1,25 +1,292 @@
+use crate::attr::Attr;
+use crate::cfg::Cfg;
+use crate::use_tree::UseTree;
+use crate::visibility::Visibility;
+
+
 /// Defines an import (`use` statement).
 #[derive(Debug, Clone)]
 pub struct Import {
-    line: String,
+    pub(crate) path: String,
+    pub(crate) tree: UseTree,
 
     /// Function visibility
-    pub vis: Option<String>,
+    pub vis: Visibility,
+
+    /// Import attributes, e.g. `#[cfg(feature = "std")]`
+    pub(crate) attrs: Vec<Attr>,
+
+    /// Whether the import is kept when unused imports are pruned
+    pub(crate) keep: bool,
+
+    /// Whether the import was split from a use tree, and is grouped with
+    /// other imports sharing the first segment of its path
+    pub(crate) grouped: bool,
+}
+
+
+/// Defines an `extern crate` declaration.
+#[derive(Debug, Clone)]
+pub struct ExternCrate {
+    pub(crate) name: String,
+    pub(crate) alias: Option<String>,
+
+    /// Declaration visibility
+    pub vis: Visibility,
+
+    /// Declaration attributes, e.g. `#[macro_use]`
+    pub(crate) attrs: Vec<Attr>,
+}
+
+
+/// Defines the order in which the imports of a scope are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ImportOrder {
+    /// Imports are written in the order they were added, grouped by
+    /// visibility.
+    #[default]
+    Insertion,
+
+    /// Imports are sorted alphabetically.
+    Sorted,
+
+    /// Imports are sorted alphabetically and split into `std`, `core` and
+    /// `alloc` imports, then imports of external crates, then `crate`,
+    /// `super` and `self` imports, separated by blank lines as rustfmt does.
+    Grouped,
+}
+
+
+/// Defines which references keep an import of a scope from being pruned.
+///
+/// Globs, imports renamed to `_`, imports with a visibility, i.e.
+/// re-exports, and imports marked with `Import::keep` are never pruned.
+///
+/// Method calls are not resolved, so a trait imported only for its methods,
+/// e.g. `std::io::Write` for `write_all`, is pruned unless it is kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PruneImports {
+    /// Imports are never pruned.
+    #[default]
+    Never,
+
+    /// Imports are pruned unless their name starts a path written by a
+    /// type, trait bound, impl target, expression or pattern of the scope.
+    Types,
+
+    /// Imports are additionally kept if their name appears as a token
+    /// anywhere in the scope, e.g. in raw body lines or macro tokens.
+    TypesAndTokens,
+}
+
+
+impl ImportOrder {
+    /// Returns the rank of the group the root path belongs to.
+    pub(crate) fn group(root: &str) -> usize {
+        match root.trim_start_matches("::").split("::").next().unwrap() {
+            "std" | "core" | "alloc" => 0,
+            "crate" | "super" | "self" => 2,
+            _ => 1,
+        }
+    }
 }
 
 
 impl Import {
-    /// Return a new import.
+    /// Return a new import of `ty` from the module at `path`.
     pub fn new(path: impl AsRef<str>, ty: impl AsRef<str>) -> Self {
         Import {
-            line: format!("{}::{}", path.as_ref(), ty.as_ref()),
-            vis: None,
+            path: path.as_ref().to_string(),
+            tree: UseTree::from(ty.as_ref()),
+            vis: Visibility::Private,
+            attrs: vec![],
+            keep: false,
+            grouped: false,
+        }
+    }
+
+    /// Return a new import of the given tree, e.g. `foo::{self, Bar}`.
+    pub fn tree(tree: impl Into<UseTree>) -> Self {
+        Import {
+            path: String::new(),
+            tree: tree.into(),
+            vis: Visibility::Private,
+            attrs: vec![],
+            keep: false,
+            grouped: false,
         }
     }
 
     /// Set the import visibility.
-    pub fn vis(&mut self, vis: impl Into<String>) -> &mut Self {
-        self.vis = Some(vis.into());
+    pub fn vis(&mut self, vis: impl Into<Visibility>) -> &mut Self {
+        self.vis = vis.into();
         self
     }
+
+    /// Set whether the import is kept when unused imports are pruned, e.g.
+    /// for a trait that is only used by method calls.
+    pub fn keep(&mut self, keep: bool) -> &mut Self {
+        self.keep = keep;
+        self
+    }
+
+    /// Push an attribute to the import.
+    pub fn push_attr(&mut self, attr: Attr) -> &mut Self {
+        self.attrs.push(attr);
+        self
+    }
+
+    /// Create an attribute on the import.
+    pub fn new_attr(&mut self, name: impl Into<String>) -> &mut Attr {
+        self.push_attr(Attr::new(name));
+
+        self.attrs.last_mut().unwrap()
+    }
+
+    /// Push a `#[cfg(pred)]` attribute.
+    pub fn cfg(&mut self, pred: Cfg) -> &mut Self {
+        self.push_attr(Attr::cfg(pred))
+    }
+
+    /// Push a `#[cfg_attr(pred, attr)]` attribute.
+    pub fn cfg_attr(&mut self, pred: Cfg, attr: Attr) -> &mut Self {
+        self.push_attr(Attr::cfg_attr(pred, attr))
+    }
+
+    /// Split the import into imports of a single leaf each, keyed by the
+    /// root path they are grouped under.
+    pub(crate) fn split(&self) -> Vec<Import> {
+        self.tree
+            .leaves()
+            .into_iter()
+            .map(|(mut segs, mut leaf)| {
+                let path = if !self.path.is_empty() {
+                    self.path.clone()
+                } else if leaf == UseTree::Glob && (segs.is_empty() || segs == [""]) {
+                    // A glob without a path, `use *;` or `use ::*;`, is
+                    // written as is
+                    segs.drain(..).map(|_| "::").collect()
+                } else {
+                    // `use foo;` is grouped as `use foo::{self};`
+                    if segs.is_empty() || segs == [""] {
+                        leaf = match leaf {
+                            UseTree::Name(name) => {
+                                segs.push(name);
+                                UseTree::name("self")
+                            }
+                            UseTree::Rename(name, alias) => {
+                                segs.push(name);
+                                UseTree::rename("self", alias)
+                            }
+                            _ => unreachable!(),
+                        };
+                    }
+
+                    let root = segs.remove(0);
+
+                    if root.is_empty() {
+                        format!("::{}", segs.remove(0))
+                    } else {
+                        root
+                    }
+                };
+
+                Import {
+                    path,
+                    tree: segs.iter().rev().fold(leaf, |tree, seg| {
+                        UseTree::Path(seg.clone(), Box::new(tree))
+                    }),
+                    vis: self.vis.clone(),
+                    attrs: self.attrs.clone(),
+                    keep: self.keep,
+                    grouped: self.grouped || self.path.is_empty(),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the full path a leaf import refers to and the name it is
+    /// imported as, e.g. `("std::fmt::Result", "FmtResult")`.
+    ///
+    /// A glob is imported as `*`.
+    pub(crate) fn binding(&self) -> (String, String) {
+        self.bindings().pop().expect("empty use tree")
+    }
+
+    /// Returns the full path and name of each leaf of the import.
+    pub(crate) fn bindings(&self) -> Vec<(String, String)> {
+        self.tree
+            .leaves()
+            .into_iter()
+            .map(|(segs, leaf)| self.leaf_binding(segs, leaf))
+            .collect()
+    }
+
+    fn leaf_binding(&self, segs: Vec<String>, leaf: UseTree) -> (String, String) {
+        let mut path = self.path.clone();
+
+        for seg in &segs {
+            path.push_str("::");
+            path.push_str(seg);
+        }
+
+        match leaf {
+            UseTree::Name(name) if name == "self" => {
+                let name = path.rsplit("::").next().unwrap().to_string();
+                (path, name)
+            }
+            UseTree::Rename(name, alias) if name == "self" => (path, alias),
+            UseTree::Name(name) => (format!("{}::{}", path, name), name),
+            UseTree::Rename(name, alias) => (format!("{}::{}", path, name), alias),
+            _ => (format!("{}::*", path), "*".to_string()),
+        }
+    }
+}
+
+
+impl ExternCrate {
+    /// Return a new `extern crate` declaration.
+    pub fn new(name: impl Into<String>) -> Self {
+        ExternCrate {
+            name: name.into(),
+            alias: None,
+            vis: Visibility::Private,
+            attrs: vec![],
+        }
+    }
+
+    /// Rename the crate, i.e. `extern crate foo as bar;`.
+    pub fn alias(&mut self, alias: impl Into<String>) -> &mut Self {
+        self.alias = Some(alias.into());
+        self
+    }
+
+    /// Set the declaration visibility.
+    pub fn vis(&mut self, vis: impl Into<Visibility>) -> &mut Self {
+        self.vis = vis.into();
+        self
+    }
+
+    /// Push an attribute to the declaration.
+    pub fn push_attr(&mut self, attr: Attr) -> &mut Self {
+        self.attrs.push(attr);
+        self
+    }
+
+    /// Create an attribute on the declaration.
+    pub fn new_attr(&mut self, name: impl Into<String>) -> &mut Attr {
+        self.push_attr(Attr::new(name));
+
+        self.attrs.last_mut().unwrap()
+    }
+
+    /// Push a `#[cfg(pred)]` attribute.
+    pub fn cfg(&mut self, pred: Cfg) -> &mut Self {
+        self.push_attr(Attr::cfg(pred))
+    }
+
+    /// Push a `#[cfg_attr(pred, attr)]` attribute.
+    pub fn cfg_attr(&mut self, pred: Cfg, attr: Attr) -> &mut Self {
+        self.push_attr(Attr::cfg_attr(pred, attr))
+    }
 }
@@ -1,5 +1,10 @@
+use crate::extern_block::ExternBlock;
 use crate::function::Function;
+use crate::macro_call::MacroCall;
+use crate::macro_rules::MacroRules;
 use crate::module::Module;
+use crate::type_alias::TypeAlias;
+use crate::union::Union;
 use crate::dis_variant::DisVariant;
 use crate::var_def::VarDef;
 
@@ -14,11 +19,16 @@ use crate::r#trait::Trait;
 pub enum Item {
     Module(Module),
     Struct(Struct),
+    Union(Union),
     Function(Function),
     Trait(Trait),
     Enum(Enum),
+    TypeAlias(TypeAlias),
     DisEnum(Enum<DisVariant>),
     Impl(Impl),
+    ExternBlock(ExternBlock),
     VarDef(VarDef),
+    MacroRules(MacroRules),
+    MacroCall(MacroCall),
     Raw(String),
 }
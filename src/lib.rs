@@ -28,26 +28,42 @@ mod assoc_type;
 mod name_ty_pair;
 mod block;
 mod body;
+mod control_flow;
 mod bound;
+mod cfg;
 mod docs;
+mod expr;
+mod extern_block;
 mod field;
 mod fields;
 mod formatter;
 mod function;
+mod generic_param;
 mod import;
 mod item;
+mod macro_call;
+mod macro_rules;
 mod module;
 mod scope;
+mod stmt;
+mod type_alias;
 mod type_def;
+mod union;
+mod use_tree;
 mod variant;
 mod dis_variant;
 mod var_def;
+mod visibility;
+mod where_predicate;
 mod attr;
 mod attr_arg;
 mod param;
+mod pat;
+mod parse;
 
 mod r#enum;
 mod r#impl;
+mod r#match;
 mod r#struct;
 mod r#trait;
 mod r#type;
@@ -55,20 +71,36 @@ mod r#type;
 
 pub use assoc_type::*;
 pub use block::*;
+pub use cfg::Cfg;
+pub use control_flow::*;
+pub use expr::*;
+pub use extern_block::*;
 pub use field::*;
 pub use formatter::*;
 pub use function::*;
+pub use generic_param::GenericParam;
 pub use import::*;
+pub use macro_call::*;
+pub use macro_rules::*;
 pub use module::*;
+pub use pat::*;
 pub use scope::*;
+pub use stmt::*;
+pub use type_alias::*;
+pub use union::*;
+pub use use_tree::UseTree;
 pub use variant::*;
 pub use dis_variant::*;
 pub use var_def::*;
+pub use visibility::Visibility;
+pub use where_predicate::WherePredicate;
 pub use attr::*;
 pub use attr_arg::*;
+pub use parse::ParseError;
 
 pub use r#enum::*;
 pub use r#impl::*;
+pub use r#match::*;
 pub use r#struct::*;
 pub use r#trait::*;
 pub use r#type::*;
@@ -0,0 +1,236 @@
+use std::fmt::{self, Write};
+
+use crate::attr::Attr;
+use crate::cfg::Cfg;
+use crate::docs::Docs;
+use crate::formatter::{Formatter, Format, dedent};
+use crate::scope::Scope;
+
+
+/// Defines a macro invocation, e.g. `lazy_static! { ... }` or
+/// `include!("gen.rs");`.
+///
+/// A call can be pushed as an item to a `Scope` or `Module`, as a statement
+/// to a function body or block, or used as an expression.
+///
+/// ```
+/// use codegen::{Delimiter, MacroCall};
+///
+/// let mut call = MacroCall::new("thread_local");
+/// call.delimiter(Delimiter::Brace);
+/// call.tokens("static COUNT: Cell<u32> = Cell::new(0);");
+/// ```
+#[derive(Debug, Clone)]
+pub struct MacroCall {
+    /// Path of the macro, without the `!`
+    path: String,
+
+    /// Delimiter around the tokens
+    delim: Delimiter,
+
+    /// Macro documentation
+    docs: Option<Docs>,
+
+    /// Macro attributes
+    attributes: Vec<Attr>,
+
+    /// Tokens passed to the macro
+    tokens: Tokens,
+
+    /// Whether a semicolon follows the call, when set explicitly
+    semi: Option<bool>,
+}
+
+
+/// Delimiter around the tokens of a macro invocation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delimiter {
+    /// `( ... )`
+    Paren,
+    /// `[ ... ]`
+    Bracket,
+    /// `{ ... }`
+    Brace,
+}
+
+
+#[derive(Debug, Clone)]
+enum Tokens {
+    Raw(Vec<String>),
+    Items(Box<Scope>),
+}
+
+
+impl MacroCall {
+    /// Return a new invocation of the given macro without tokens. A trailing
+    /// `!` in the path is optional.
+    pub fn new(path: impl Into<String>) -> Self {
+        let mut path = path.into();
+
+        if path.ends_with('!') {
+            path.pop();
+        }
+
+        MacroCall {
+            path,
+            delim: Delimiter::Paren,
+            docs: None,
+            attributes: vec![],
+            tokens: Tokens::Raw(vec![]),
+            semi: None,
+        }
+    }
+
+    /// Set the delimiter around the tokens. Defaults to parentheses.
+    pub fn delimiter(&mut self, delim: Delimiter) -> &mut Self {
+        self.delim = delim;
+        self
+    }
+
+    /// Set the macro documentation.
+    pub fn doc(&mut self, docs: impl Into<String>) -> &mut Self {
+        self.docs = Some(Docs::new(docs));
+        self
+    }
+
+    /// Add an attribute to the macro invocation.
+    pub fn attr(&mut self, attribute: impl Into<String>) -> &mut Self {
+        self.push_attr(Attr::from(attribute.into()))
+    }
+
+    /// Push an attribute to the macro invocation.
+    pub fn push_attr(&mut self, attr: Attr) -> &mut Self {
+        self.attributes.push(attr);
+        self
+    }
+
+    /// Create an attribute on the macro invocation.
+    pub fn new_attr(&mut self, name: impl Into<String>) -> &mut Attr {
+        self.push_attr(Attr::new(name));
+
+        self.attributes.last_mut().unwrap()
+    }
+
+    /// Push a `#[cfg(pred)]` attribute.
+    pub fn cfg(&mut self, pred: Cfg) -> &mut Self {
+        self.push_attr(Attr::cfg(pred))
+    }
+
+    /// Push a `#[cfg_attr(pred, attr)]` attribute.
+    pub fn cfg_attr(&mut self, pred: Cfg, attr: Attr) -> &mut Self {
+        self.push_attr(Attr::cfg_attr(pred, attr))
+    }
+
+    /// Set the tokens passed to the macro, replacing any previous tokens.
+    ///
+    /// Single-line tokens are written inline, e.g. `include!("gen.rs")`.
+    /// Multi-line tokens have their common indentation removed and are
+    /// re-indented inside the delimiters.
+    pub fn tokens(&mut self, tokens: impl AsRef<str>) -> &mut Self {
+        self.tokens = Tokens::Raw(dedent(tokens.as_ref()));
+        self
+    }
+
+    /// Returns a mutable reference to a scope whose items are passed to the
+    /// macro as tokens, e.g. the struct given to `bitflags!`.
+    ///
+    /// Any raw tokens are replaced.
+    pub fn scope(&mut self) -> &mut Scope {
+        if let Tokens::Raw(_) = self.tokens {
+            self.tokens = Tokens::Items(Box::default());
+        }
+
+        match &mut self.tokens {
+            Tokens::Items(scope) => scope,
+            Tokens::Raw(_) => unreachable!(),
+        }
+    }
+
+    /// Set whether a semicolon follows the invocation when used as an item
+    /// or statement.
+    ///
+    /// By default, a semicolon is written unless the tokens are delimited by
+    /// braces.
+    pub fn semi(&mut self, semi: bool) -> &mut Self {
+        self.semi = Some(semi);
+        self
+    }
+
+    fn needs_semi(&self) -> bool {
+        self.semi.unwrap_or(self.delim != Delimiter::Brace)
+    }
+
+    /// Returns true if the invocation ends with a brace-delimited body.
+    pub(crate) fn is_braced(&self) -> bool {
+        self.delim == Delimiter::Brace
+    }
+
+    /// Formats the invocation as an expression, without docs, attributes
+    /// or a trailing semicolon.
+    pub fn fmt_call(&self, fmt: &mut Formatter) -> fmt::Result {
+        let (open, close) = match self.delim {
+            Delimiter::Paren => ("(", ")"),
+            Delimiter::Bracket => ("[", "]"),
+            Delimiter::Brace => ("{", "}"),
+        };
+
+        fmt.write_path(&self.path)?;
+        write!(fmt, "!")?;
+
+        if self.delim == Delimiter::Brace {
+            write!(fmt, " ")?;
+        }
+
+        match &self.tokens {
+            Tokens::Raw(lines) if lines.len() <= 1 => {
+                write!(fmt, "{}", open)?;
+
+                if let Some(line) = lines.first() {
+                    write!(fmt, "{}", line)?;
+                }
+
+                write!(fmt, "{}", close)
+            }
+            Tokens::Raw(lines) => {
+                writeln!(fmt, "{}", open)?;
+
+                fmt.indent(|fmt| {
+                    for line in lines {
+                        writeln!(fmt, "{}", line)?;
+                    }
+
+                    Ok(())
+                })?;
+
+                write!(fmt, "{}", close)
+            }
+            Tokens::Items(scope) => {
+                writeln!(fmt, "{}", open)?;
+                fmt.indent(|fmt| scope.fmt_nested(fmt))?;
+                write!(fmt, "{}", close)
+            }
+        }
+    }
+}
+
+
+impl Format for MacroCall {
+    /// Formats the invocation as an item using the given formatter.
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        if let Some(docs) = &self.docs {
+            docs.fmt(fmt, false)?;
+        }
+
+        for attr in &self.attributes {
+            attr.fmt(fmt, false)?;
+        }
+
+        self.fmt_call(fmt)?;
+
+        if self.needs_semi() {
+            write!(fmt, ";")?;
+        }
+
+        Ok(())
+    }
+}
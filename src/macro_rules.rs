@@ -0,0 +1,158 @@
+use std::fmt::{self, Write};
+
+use crate::attr::Attr;
+use crate::cfg::Cfg;
+use crate::docs::Docs;
+use crate::formatter::{Formatter, Format, dedent};
+
+
+/// Defines a declarative macro, i.e. `macro_rules! name { ... }`.
+///
+/// ```
+/// use codegen::MacroRules;
+///
+/// let mut mac = MacroRules::new("square");
+/// mac.set_export(true);
+/// mac.arm("$x:expr", "$x * $x");
+/// ```
+#[derive(Debug, Clone)]
+pub struct MacroRules {
+    /// Macro name
+    name: String,
+
+    /// Macro documentation
+    docs: Option<Docs>,
+
+    /// Macro attributes, e.g. `allow(unused_macros)`
+    attributes: Vec<Attr>,
+
+    /// Rules of the macro
+    arms: Vec<MacroArm>,
+}
+
+
+/// A rule of a declarative macro.
+#[derive(Debug, Clone)]
+struct MacroArm {
+    matcher: String,
+    transcriber: Vec<String>,
+}
+
+
+impl MacroRules {
+    /// Return a new macro definition without rules.
+    pub fn new(name: impl Into<String>) -> Self {
+        MacroRules {
+            name: name.into(),
+            docs: None,
+            attributes: vec![],
+            arms: vec![],
+        }
+    }
+
+    /// Set the macro documentation.
+    pub fn doc(&mut self, docs: impl Into<String>) -> &mut Self {
+        self.docs = Some(Docs::new(docs));
+        self
+    }
+
+    /// Set whether the macro is exported from the crate with
+    /// `#[macro_export]`.
+    pub fn set_export(&mut self, export: bool) -> &mut Self {
+        let exported = self.attributes.iter().any(|attr| attr.name == "macro_export");
+
+        if export && !exported {
+            self.push_attr(Attr::new("macro_export"));
+        } else if !export {
+            self.attributes.retain(|attr| attr.name != "macro_export");
+        }
+
+        self
+    }
+
+    /// Add an attribute to the macro.
+    pub fn attr(&mut self, attribute: impl Into<String>) -> &mut Self {
+        self.push_attr(Attr::from(attribute.into()))
+    }
+
+    /// Push an attribute to the macro.
+    pub fn push_attr(&mut self, attr: Attr) -> &mut Self {
+        self.attributes.push(attr);
+        self
+    }
+
+    /// Create an attribute on the macro.
+    pub fn new_attr(&mut self, name: impl Into<String>) -> &mut Attr {
+        self.push_attr(Attr::new(name));
+
+        self.attributes.last_mut().unwrap()
+    }
+
+    /// Push a `#[cfg(pred)]` attribute.
+    pub fn cfg(&mut self, pred: Cfg) -> &mut Self {
+        self.push_attr(Attr::cfg(pred))
+    }
+
+    /// Push a `#[cfg_attr(pred, attr)]` attribute.
+    pub fn cfg_attr(&mut self, pred: Cfg, attr: Attr) -> &mut Self {
+        self.push_attr(Attr::cfg_attr(pred, attr))
+    }
+
+    /// Push a rule to the macro.
+    ///
+    /// The matcher is given without its enclosing parentheses, e.g.
+    /// `"$x:expr"`. The transcriber may span multiple lines; its common
+    /// leading indentation is removed and it is re-indented to the rule.
+    pub fn arm<M, T>(&mut self, matcher: M, transcriber: T) -> &mut Self
+    where
+        M: Into<String>,
+        T: AsRef<str>,
+    {
+        self.arms.push(MacroArm {
+            matcher: matcher.into(),
+            transcriber: dedent(transcriber.as_ref()),
+        });
+
+        self
+    }
+}
+
+
+impl Format for MacroRules {
+    /// Formats the macro using the given formatter.
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        if let Some(docs) = &self.docs {
+            docs.fmt(fmt, false)?;
+        }
+
+        for attr in &self.attributes {
+            attr.fmt(fmt, false)?;
+        }
+
+        write!(fmt, "macro_rules! {}", self.name)?;
+
+        fmt.block(|fmt| {
+            for arm in &self.arms {
+                write!(fmt, "({}) =>", arm.matcher)?;
+
+                if arm.transcriber.is_empty() {
+                    writeln!(fmt, " {{}};")?;
+                    continue;
+                }
+
+                fmt.block(|fmt| {
+                    for line in &arm.transcriber {
+                        writeln!(fmt, "{}", line)?;
+                    }
+
+                    Ok(())
+                })?;
+
+                writeln!(fmt, ";")?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
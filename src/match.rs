@@ -0,0 +1,153 @@
+use std::fmt::{self, Write};
+
+use crate::block::Block;
+use crate::expr::Expr;
+use crate::formatter::Formatter;
+use crate::pat::Pat;
+use crate::stmt::{Let, Stmt};
+
+
+/// Defines a `match` expression.
+#[derive(Debug, Clone)]
+pub struct Match {
+    expr: Expr,
+    arms: Vec<Arm>,
+}
+
+
+/// Defines an arm of a `match` expression.
+///
+/// An arm whose body is a single expression is written on one line, any
+/// other body is written as a block.
+#[derive(Debug, Clone)]
+pub struct Arm {
+    pat: Pat,
+    guard: Option<Expr>,
+    body: Block,
+}
+
+
+impl Match {
+    /// Return a new `match` expression on the given value.
+    pub fn new(expr: impl Into<Expr>) -> Self {
+        Match {
+            expr: expr.into(),
+            arms: vec![],
+        }
+    }
+
+    /// Push a new arm, returning a mutable reference to it.
+    pub fn new_arm(&mut self, pat: impl Into<Pat>) -> &mut Arm {
+        self.push_arm(Arm::new(pat));
+        self.arms.last_mut().unwrap()
+    }
+
+    /// Push an arm.
+    pub fn push_arm(&mut self, arm: Arm) -> &mut Self {
+        self.arms.push(arm);
+        self
+    }
+
+    /// Push an arm with a single expression as its body.
+    pub fn arm<P, E>(&mut self, pat: P, body: E) -> &mut Self
+    where
+        P: Into<Pat>,
+        E: Into<Expr>,
+    {
+        self.new_arm(pat).push_expr(body);
+        self
+    }
+
+    /// Formats the `match` expression using the given formatter.
+    pub fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        write!(fmt, "match ")?;
+        self.expr.fmt(fmt)?;
+
+        fmt.block(|fmt| {
+            for arm in &self.arms {
+                arm.fmt(fmt)?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+impl From<Match> for Expr {
+    fn from(src: Match) -> Self {
+        Expr::Match(Box::new(src))
+    }
+}
+
+
+impl Arm {
+    /// Return a new arm matching the given pattern.
+    pub fn new(pat: impl Into<Pat>) -> Self {
+        Arm {
+            pat: pat.into(),
+            guard: None,
+            body: Block::default(),
+        }
+    }
+
+    /// Set the `if` guard.
+    pub fn guard(&mut self, expr: impl Into<Expr>) -> &mut Self {
+        self.guard = Some(expr.into());
+        self
+    }
+
+    /// Push a line to the arm body.
+    pub fn line<T>(&mut self, line: T) -> &mut Self
+    where
+        T: ToString,
+    {
+        self.body.line(line);
+        self
+    }
+
+    /// Push a statement to the arm body.
+    pub fn push_stmt(&mut self, stmt: impl Into<Stmt>) -> &mut Self {
+        self.body.push_stmt(stmt);
+        self
+    }
+
+    /// Push an expression without a trailing semicolon to the arm body.
+    pub fn push_expr(&mut self, expr: impl Into<Expr>) -> &mut Self {
+        self.body.push_expr(expr);
+        self
+    }
+
+    /// Push a new `let` binding to the arm body, returning a mutable
+    /// reference to it.
+    pub fn new_let(&mut self, pat: impl Into<String>) -> &mut Let {
+        self.body.new_let(pat)
+    }
+
+    /// Formats the arm using the given formatter.
+    pub fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        self.pat.fmt(fmt)?;
+
+        if let Some(guard) = &self.guard {
+            write!(fmt, " if ")?;
+            guard.fmt(fmt)?;
+        }
+
+        write!(fmt, " => ")?;
+
+        match self.body.single_expr() {
+            Some(expr) if expr.is_block_like() => {
+                expr.fmt(fmt)?;
+                writeln!(fmt)
+            }
+            Some(expr) => {
+                expr.fmt(fmt)?;
+                writeln!(fmt, ",")
+            }
+            None if self.body.is_empty() => writeln!(fmt, "{{}}"),
+            None => {
+                self.body.fmt_inline(fmt)?;
+                writeln!(fmt)
+            }
+        }
+    }
+}
@@ -2,9 +2,18 @@ use std::fmt::{self, Write};
 
 use crate::docs::Docs;
 use crate::formatter::{Formatter, Format};
+use crate::extern_block::ExternBlock;
 use crate::function::Function;
+use crate::import::{ExternCrate, Import, ImportOrder, PruneImports};
+use crate::macro_call::MacroCall;
+use crate::macro_rules::MacroRules;
 use crate::scope::Scope;
+use crate::use_tree::UseTree;
+use crate::type_alias::TypeAlias;
+use crate::union::Union;
+use crate::visibility::Visibility;
 use crate::attr::Attr;
+use crate::cfg::Cfg;
 
 use crate::r#enum::Enum;
 use crate::r#impl::Impl;
@@ -20,7 +29,7 @@ pub struct Module {
     pub name: String,
 
     /// Visibility
-    pub vis: Option<String>,
+    pub vis: Visibility,
 
     /// Module documentation
     docs: Option<Docs>,
@@ -38,7 +47,7 @@ impl Module {
     pub fn new(name: impl Into<String>) -> Self {
         Module {
             name: name.into(),
-            vis: None,
+            vis: Visibility::Private,
             docs: None,
             attrs: vec![],
             scope: Scope::new(),
@@ -51,8 +60,8 @@ impl Module {
     }
 
     /// Set the module visibility.
-    pub fn vis(&mut self, vis: impl Into<String>) -> &mut Self {
-        self.vis = Some(vis.into());
+    pub fn vis(&mut self, vis: impl Into<Visibility>) -> &mut Self {
+        self.vis = vis.into();
         self
     }
 
@@ -74,6 +83,22 @@ impl Module {
         self.attrs.last_mut().unwrap()
     }
 
+    /// Push a `#[cfg(pred)]` attribute.
+    pub fn cfg(&mut self, pred: Cfg) -> &mut Self {
+        self.push_attr(Attr::cfg(pred))
+    }
+
+    /// Push a `#[cfg_attr(pred, attr)]` attribute.
+    pub fn cfg_attr(&mut self, pred: Cfg, attr: Attr) -> &mut Self {
+        self.push_attr(Attr::cfg_attr(pred, attr))
+    }
+
+    /// Make the macros defined in the module usable after it, i.e.
+    /// `#[macro_use]`.
+    pub fn macro_use(&mut self) -> &mut Self {
+        self.push_attr(Attr::new("macro_use"))
+    }
+
     /// Import a type into the module's scope.
     ///
     /// This results in a new `use` statement bein added to the beginning of the
@@ -87,6 +112,45 @@ impl Module {
         self
     }
 
+    /// Set whether qualified type paths are imported automatically into the
+    /// module's scope. See `Scope::auto_import`.
+    pub fn auto_import(&mut self, enabled: bool) -> &mut Self {
+        self.scope.auto_import(enabled);
+        self
+    }
+
+    /// Set the order in which the imports of the module are written. See
+    /// `Scope::import_order`.
+    pub fn import_order(&mut self, order: ImportOrder) -> &mut Self {
+        self.scope.import_order(order);
+        self
+    }
+
+    /// Set whether imports that are not referenced by the module are
+    /// dropped. See `Scope::prune_imports`.
+    pub fn prune_imports(&mut self, prune: PruneImports) -> &mut Self {
+        self.scope.prune_imports(prune);
+        self
+    }
+
+    /// Import a use tree into the module's scope, e.g. `foo::{self, bar::*}`.
+    pub fn import_tree(&mut self, tree: impl Into<UseTree>) -> &mut Self {
+        self.scope.import_tree(tree);
+        self
+    }
+
+    /// Push an import to the module's scope.
+    pub fn push_import(&mut self, import: Import) -> &mut Self {
+        self.scope.push_import(import);
+        self
+    }
+
+    /// Declare an external crate in the module's scope, returning a mutable
+    /// reference to the declaration.
+    pub fn extern_crate(&mut self, name: impl Into<String>) -> &mut ExternCrate {
+        self.scope.extern_crate(name)
+    }
+
     /// Push a new module definition, returning a mutable reference to it.
     ///
     /// # Panics
@@ -159,6 +223,32 @@ impl Module {
         self
     }
 
+    /// Push a new union definition, returning a mutable reference to it.
+    pub fn new_union(&mut self, name: impl Into<String>) -> &mut Union {
+        self.scope.new_union(name)
+    }
+
+    /// Push a union definition
+    pub fn push_union(&mut self, item: Union) -> &mut Self {
+        self.scope.push_union(item);
+        self
+    }
+
+    /// Push a new type alias, returning a mutable reference to it.
+    pub fn new_type_alias<S, T>(&mut self, name: S, target: T) -> &mut TypeAlias
+    where
+        S: Into<String>,
+        T: Into<Type>,
+    {
+        self.scope.new_type_alias(name, target)
+    }
+
+    /// Push a type alias.
+    pub fn push_type_alias(&mut self, item: TypeAlias) -> &mut Self {
+        self.scope.push_type_alias(item);
+        self
+    }
+
     /// Push a new function definition, returning a mutable reference to it.
     pub fn new_fn(&mut self, name: impl Into<String>) -> &mut Function {
         self.scope.new_fn(name)
@@ -192,6 +282,41 @@ impl Module {
         self
     }
 
+    /// Push a new `extern` block with the given ABI, returning a mutable
+    /// reference to it.
+    pub fn new_extern_block(&mut self, abi: impl Into<String>) -> &mut ExternBlock {
+        self.scope.new_extern_block(abi)
+    }
+
+    /// Push an `extern` block.
+    pub fn push_extern_block(&mut self, item: ExternBlock) -> &mut Self {
+        self.scope.push_extern_block(item);
+        self
+    }
+
+    /// Push a new `macro_rules!` definition, returning a mutable reference
+    /// to it.
+    pub fn new_macro_rules(&mut self, name: impl Into<String>) -> &mut MacroRules {
+        self.scope.new_macro_rules(name)
+    }
+
+    /// Push a `macro_rules!` definition.
+    pub fn push_macro_rules(&mut self, item: MacroRules) -> &mut Self {
+        self.scope.push_macro_rules(item);
+        self
+    }
+
+    /// Push a new macro invocation, returning a mutable reference to it.
+    pub fn new_macro_call(&mut self, path: impl Into<String>) -> &mut MacroCall {
+        self.scope.new_macro_call(path)
+    }
+
+    /// Push a macro invocation.
+    pub fn push_macro_call(&mut self, item: MacroCall) -> &mut Self {
+        self.scope.push_macro_call(item);
+        self
+    }
+
     /// Push a trait definition
     pub fn push_trait(&mut self, item: Trait) -> &mut Self {
         self.scope.push_trait(item);
@@ -211,8 +336,8 @@ impl Format for Module {
             attr.fmt(fmt, false)?;
         }
 
-        if let Some(ref vis) = self.vis {
-            write!(fmt, "{} ", vis)?;
+        if !self.vis.is_private() {
+            write!(fmt, "{} ", self.vis)?;
         }
 
         write!(fmt, "mod {}", self.name)?;
@@ -1,3 +1,5 @@
+use crate::attr::Attr;
+
 use crate::r#type::Type;
 
 
@@ -8,4 +10,5 @@ pub(crate) struct Param {
     pub(crate) modi: Option<String>,
     pub(crate) name: String,
     pub(crate) ty: Type,
+    pub(crate) attrs: Vec<Attr>,
 }
\ No newline at end of file
@@ -0,0 +1,620 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::r#type::Type;
+
+
+/// An error returned when a string cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    msg: String,
+    pos: usize,
+}
+
+
+impl ParseError {
+    pub(crate) fn new(msg: impl Into<String>, pos: usize) -> Self {
+        ParseError {
+            msg: msg.into(),
+            pos,
+        }
+    }
+
+    /// Returns the byte offset in the input at which parsing failed.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at offset {}", self.msg, self.pos)
+    }
+}
+
+impl Error for ParseError {}
+
+
+/// A cursor over a source string.
+pub(crate) struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+
+impl<'a> Parser<'a> {
+    pub(crate) fn new(src: &'a str) -> Self {
+        Parser { src, pos: 0 }
+    }
+
+    /// Returns an error describing the current position.
+    pub(crate) fn error<T>(&self, msg: impl Into<String>) -> Result<T, ParseError> {
+        Err(ParseError::new(msg, self.pos))
+    }
+
+    /// Returns an error unless the whole input was consumed.
+    pub(crate) fn finish(&mut self) -> Result<(), ParseError> {
+        self.skip_ws();
+
+        match self.peek() {
+            Some(c) => self.error(format!("unexpected `{}`", c)),
+            None => Ok(()),
+        }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+
+    pub(crate) fn skip_ws(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Returns the next non-whitespace character without consuming it.
+    pub(crate) fn peek(&mut self) -> Option<char> {
+        self.skip_ws();
+        self.rest().chars().next()
+    }
+
+    /// Returns whether the remaining input starts with `s`, without
+    /// consuming it.
+    pub(crate) fn peek_str(&mut self, s: &str) -> bool {
+        self.skip_ws();
+        self.rest().starts_with(s)
+    }
+
+    /// Consumes `s` if the remaining input starts with it.
+    pub(crate) fn eat(&mut self, s: &str) -> bool {
+        if self.peek_str(s) {
+            self.pos += s.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Consumes `s` or returns an error.
+    pub(crate) fn expect(&mut self, s: &str) -> Result<(), ParseError> {
+        if self.eat(s) {
+            Ok(())
+        } else {
+            self.error(format!("expected `{}`", s))
+        }
+    }
+
+    /// Consumes the keyword `kw` if it is not the prefix of a longer
+    /// identifier.
+    pub(crate) fn eat_keyword(&mut self, kw: &str) -> bool {
+        if !self.peek_str(kw) {
+            return false;
+        }
+
+        match self.rest()[kw.len()..].chars().next() {
+            Some(c) if is_ident_char(c) => false,
+            _ => {
+                self.pos += kw.len();
+                true
+            }
+        }
+    }
+
+    /// Consumes an identifier, including raw identifiers such as `r#type`.
+    pub(crate) fn ident(&mut self) -> Option<String> {
+        self.skip_ws();
+
+        let rest = self.rest();
+        let start = if rest.starts_with("r#") { 2 } else { 0 };
+
+        match rest[start..].chars().next() {
+            Some(c) if c == '_' || c.is_alphabetic() => {}
+            _ => return None,
+        }
+
+        let len = rest[start..]
+            .find(|c: char| !is_ident_char(c))
+            .unwrap_or(rest.len() - start);
+
+        self.pos += start + len;
+        Some(rest[..start + len].to_string())
+    }
+
+    /// Consumes a lifetime, e.g. `'a`.
+    pub(crate) fn lifetime(&mut self) -> Option<String> {
+        if !self.peek_str("'") {
+            return None;
+        }
+
+        let start = self.pos;
+        self.pos += 1;
+
+        match self.ident() {
+            Some(name) if !self.rest().starts_with('\'') => {
+                Some(format!("'{}", name))
+            }
+            _ => {
+                // A char literal, not a lifetime
+                self.pos = start;
+                None
+            }
+        }
+    }
+
+    /// Consumes a numeric literal.
+    pub(crate) fn number(&mut self) -> Option<String> {
+        self.skip_ws();
+
+        let rest = self.rest();
+
+        if !rest.starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+
+        let len = rest.find(|c: char| !is_ident_char(c)).unwrap_or(rest.len());
+        self.pos += len;
+        Some(rest[..len].to_string())
+    }
+
+    /// Consumes a string literal, including raw strings such as `r#"..."#`,
+    /// and returns its unescaped contents.
+    pub(crate) fn str_lit(&mut self) -> Result<Option<String>, ParseError> {
+        self.skip_ws();
+
+        let rest = self.rest();
+
+        if let Some(raw) = rest.strip_prefix('r') {
+            let hashes = raw.len() - raw.trim_start_matches('#').len();
+
+            if !rest[1 + hashes..].starts_with('"') {
+                return Ok(None);
+            }
+
+            let body = &rest[2 + hashes..];
+            let close = format!("\"{}", "#".repeat(hashes));
+
+            return match body.find(&close) {
+                Some(end) => {
+                    self.pos += 2 + hashes + end + close.len();
+                    Ok(Some(body[..end].to_string()))
+                }
+                None => self.error("unterminated string literal"),
+            };
+        }
+
+        if !rest.starts_with('"') {
+            return Ok(None);
+        }
+
+        let mut value = String::new();
+        let mut chars = rest.char_indices().skip(1);
+
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += i + 1;
+                    return Ok(Some(value));
+                }
+                '\\' => {
+                    let escaped = match chars.next() {
+                        Some((_, 'n')) => '\n',
+                        Some((_, 'r')) => '\r',
+                        Some((_, 't')) => '\t',
+                        Some((_, '0')) => '\0',
+                        Some((_, c @ '\\')) | Some((_, c @ '"')) | Some((_, c @ '\'')) => c,
+                        Some((j, 'u')) => {
+                            let code = rest[j + 1..]
+                                .strip_prefix('{')
+                                .and_then(|s| s.split('}').next())
+                                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                                .and_then(char::from_u32);
+
+                            match code {
+                                Some(c) => {
+                                    // Skip the `{...}`
+                                    for (_, c) in chars.by_ref() {
+                                        if c == '}' {
+                                            break;
+                                        }
+                                    }
+                                    c
+                                }
+                                None => {
+                                    self.pos += j;
+                                    return self.error("invalid unicode escape");
+                                }
+                            }
+                        }
+                        _ => {
+                            self.pos += i;
+                            return self.error("invalid escape");
+                        }
+                    };
+
+                    value.push(escaped);
+                }
+                c => value.push(c),
+            }
+        }
+
+        self.error("unterminated string literal")
+    }
+
+    /// Consumes and returns the remaining input.
+    pub(crate) fn take_rest(&mut self) -> &'a str {
+        let rest = self.rest();
+        self.pos = self.src.len();
+        rest
+    }
+
+    /// Consumes raw source text up to, but not including, the first
+    /// unbalanced closing delimiter or top-level `,`.
+    pub(crate) fn raw_until_close(&mut self) -> Result<String, ParseError> {
+        self.skip_ws();
+
+        let rest = self.rest();
+        let mut depth = 0usize;
+        let mut end = rest.len();
+        let mut chars = rest.char_indices();
+
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' if depth == 0 => {
+                    end = i;
+                    break;
+                }
+                ')' | ']' | '}' => depth -= 1,
+                ',' if depth == 0 => {
+                    end = i;
+                    break;
+                }
+                '"' => skip_str(&mut chars),
+                _ => {}
+            }
+        }
+
+        let raw = rest[..end].trim_end();
+
+        if raw.is_empty() {
+            return self.error("expected an expression");
+        }
+
+        self.pos += end;
+        Ok(raw.to_string())
+    }
+
+    /// Consumes balanced raw source text up to, but not including, the
+    /// closing delimiter matching an already consumed opening one.
+    pub(crate) fn raw_until_delim(&mut self, close: char) -> Result<&'a str, ParseError> {
+        let rest = self.rest();
+        let mut stack = vec![close];
+        let mut chars = rest.char_indices();
+
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '(' => stack.push(')'),
+                '[' => stack.push(']'),
+                '{' => stack.push('}'),
+                ')' | ']' | '}' => {
+                    if stack.pop() != Some(c) {
+                        self.pos += i;
+                        return self.error(format!("unexpected `{}`", c));
+                    }
+
+                    if stack.is_empty() {
+                        self.pos += i;
+                        return Ok(&rest[..i]);
+                    }
+                }
+                '"' => skip_str(&mut chars),
+                _ => {}
+            }
+        }
+
+        self.pos = self.src.len();
+        self.error(format!("expected `{}`", close))
+    }
+
+    /// Parses a type.
+    pub(crate) fn parse_type(&mut self) -> Result<Type, ParseError> {
+        if self.eat("&&") {
+            let inner = self.parse_ref_tail()?;
+            return Ok(Type::reference(None, false, inner));
+        }
+
+        if self.eat("&") {
+            return self.parse_ref_tail();
+        }
+
+        if self.eat("*") {
+            let mutable = if self.eat_keyword("mut") {
+                true
+            } else if self.eat_keyword("const") {
+                false
+            } else {
+                return self.error("expected `const` or `mut`");
+            };
+
+            return Ok(Type::ptr(mutable, self.parse_type()?));
+        }
+
+        if self.eat("(") {
+            let (tys, trailing) = self.parse_list(")", Parser::parse_type)?;
+
+            if tys.len() == 1 && !trailing {
+                return Ok(tys.into_iter().next().unwrap());
+            }
+
+            return Ok(Type::Tuple(tys));
+        }
+
+        if self.eat("[") {
+            let inner = self.parse_type()?;
+
+            if self.eat(";") {
+                let len = self.raw_until_close()?;
+                self.expect("]")?;
+                return Ok(Type::array(inner, len));
+            }
+
+            self.expect("]")?;
+            return Ok(Type::slice(inner));
+        }
+
+        if self.eat("<") {
+            let qself = self.parse_type()?;
+
+            let as_trait = if self.eat_keyword("as") {
+                Some(self.parse_type()?)
+            } else {
+                None
+            };
+
+            self.expect(">")?;
+            self.expect("::")?;
+
+            let name = self.parse_path_segments()?;
+            return Ok(Type::qpath(qself, as_trait, name));
+        }
+
+        if self.eat("!") {
+            return Ok(Type::new("!"));
+        }
+
+        if let Some(lifetime) = self.lifetime() {
+            return Ok(Type::Lifetime(lifetime));
+        }
+
+        if let Some(number) = self.number() {
+            // A const generic argument
+            return Ok(Type::new(number));
+        }
+
+        if self.eat_keyword("dyn") {
+            return Ok(Type::DynTrait(self.parse_bounds()?));
+        }
+
+        if self.eat_keyword("impl") {
+            return Ok(Type::ImplTrait(self.parse_bounds()?));
+        }
+
+        let start = self.pos;
+
+        if self.eat_keyword("fn") {
+            if self.peek_str("(") {
+                let (params, ret) = self.parse_fn_sig()?;
+                return Ok(Type::FnPtr { params, ret });
+            }
+
+            self.pos = start;
+        }
+
+        self.parse_path()
+    }
+
+    /// Parses the part of a reference type following the `&`.
+    fn parse_ref_tail(&mut self) -> Result<Type, ParseError> {
+        let lifetime = self.lifetime();
+        let mutable = self.eat_keyword("mut");
+        let inner = self.parse_type()?;
+
+        Ok(Type::Reference {
+            lifetime,
+            mutable,
+            inner: Box::new(inner),
+        })
+    }
+
+    /// Parses a path type, e.g. `std::vec::Vec<T>` or `FnMut(u32)`.
+    fn parse_path(&mut self) -> Result<Type, ParseError> {
+        let mut name = String::new();
+
+        if self.eat("::") {
+            name.push_str("::");
+        }
+
+        name.push_str(&self.parse_path_segments()?);
+
+        if self.peek_str("(") {
+            match name.rsplit("::").next() {
+                Some("Fn") | Some("FnMut") | Some("FnOnce") => {
+                    let (params, ret) = self.parse_fn_sig()?;
+                    return Ok(Type::FnTrait { name, params, ret });
+                }
+                _ => {}
+            }
+        }
+
+        let mut generics = vec![];
+
+        if self.eat("::<") || self.eat("<") {
+            generics = self.parse_list(">", Parser::parse_generic_arg)?.0;
+
+            if self.peek_str("::") {
+                return self.error("generic arguments are only supported \
+                                   on the last path segment");
+            }
+        }
+
+        Ok(Type::Path { name, generics })
+    }
+
+    /// Parses `::`-separated identifiers, stopping before any generic
+    /// arguments.
+    fn parse_path_segments(&mut self) -> Result<String, ParseError> {
+        let mut name = match self.ident() {
+            Some(ident) => ident,
+            None => return self.error("expected a type"),
+        };
+
+        loop {
+            let start = self.pos;
+
+            if !self.eat("::") {
+                break;
+            }
+
+            match self.ident() {
+                Some(ident) => {
+                    name.push_str("::");
+                    name.push_str(&ident);
+                }
+                None => {
+                    // Could be the start of a turbofish
+                    self.pos = start;
+                    break;
+                }
+            }
+        }
+
+        Ok(name)
+    }
+
+    /// Parses a generic argument, which may be an associated type binding.
+    fn parse_generic_arg(&mut self) -> Result<Type, ParseError> {
+        let start = self.pos;
+
+        if let Some(name) = self.ident() {
+            if self.eat("=") {
+                return Ok(Type::binding(name, self.parse_type()?));
+            }
+
+            self.pos = start;
+        }
+
+        self.parse_type()
+    }
+
+    /// Parses `(A, B) -> C`.
+    fn parse_fn_sig(&mut self) -> Result<(Vec<Type>, Option<Box<Type>>), ParseError> {
+        self.expect("(")?;
+
+        let params = self.parse_list(")", Parser::parse_type)?.0;
+
+        let ret = if self.eat("->") {
+            Some(Box::new(self.parse_type()?))
+        } else {
+            None
+        };
+
+        Ok((params, ret))
+    }
+
+    /// Parses `+`-separated bounds.
+    pub(crate) fn parse_bounds(&mut self) -> Result<Vec<Type>, ParseError> {
+        let mut bounds = vec![self.parse_bound()?];
+
+        while self.eat("+") {
+            bounds.push(self.parse_bound()?);
+        }
+
+        Ok(bounds)
+    }
+
+    /// Parses a single bound, e.g. `Clone`, `'a` or `?Sized`.
+    fn parse_bound(&mut self) -> Result<Type, ParseError> {
+        if self.eat("?") {
+            return match self.parse_path()? {
+                Type::Path { name, generics } => Ok(Type::Path {
+                    name: format!("?{}", name),
+                    generics,
+                }),
+                _ => self.error("expected a trait"),
+            };
+        }
+
+        if let Some(lifetime) = self.lifetime() {
+            return Ok(Type::Lifetime(lifetime));
+        }
+
+        self.parse_path()
+    }
+
+    /// Parses a comma-separated list terminated by `close`. The opening
+    /// delimiter must already be consumed.
+    ///
+    /// Returns the items and whether the list had a trailing comma.
+    pub(crate) fn parse_list<T, F>(
+        &mut self,
+        close: &str,
+        mut item: F,
+    ) -> Result<(Vec<T>, bool), ParseError>
+    where
+        F: FnMut(&mut Self) -> Result<T, ParseError>,
+    {
+        let mut items = vec![];
+        let mut trailing = false;
+
+        while !self.eat(close) {
+            items.push(item(self)?);
+
+            trailing = self.eat(",");
+
+            if !trailing {
+                self.expect(close)?;
+                break;
+            }
+        }
+
+        Ok((items, trailing))
+    }
+}
+
+
+fn is_ident_char(c: char) -> bool {
+    c == '_' || c.is_alphanumeric()
+}
+
+
+/// Skips over the rest of a string literal whose opening `"` was consumed.
+fn skip_str(chars: &mut std::str::CharIndices) {
+    while let Some((_, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '"' => break,
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,379 @@
+use std::fmt::{self, Write};
+
+use crate::formatter::Formatter;
+
+
+/// Defines a pattern, as used by `match` arms and `if let`.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pat {
+    /// The wildcard pattern, `_`.
+    Wild,
+
+    /// The rest pattern, `..`.
+    Rest,
+
+    /// A binding, e.g. `x`, `ref mut x` or `x @ 1..=5`.
+    Ident {
+        /// Whether the binding is by reference
+        by_ref: bool,
+        /// Whether the binding is mutable
+        mutable: bool,
+        /// Binding name
+        name: String,
+        /// Subpattern following `@`
+        sub: Option<Box<Pat>>,
+    },
+
+    /// A path, e.g. `None` or `Ordering::Less`.
+    Path(String),
+
+    /// A literal, e.g. `1` or `"foo"`.
+    Lit(String),
+
+    /// A range of literals, e.g. `1..=5` or `'a'..`.
+    Range {
+        /// Start of the range
+        start: Option<String>,
+        /// End of the range
+        end: Option<String>,
+        /// Whether the range includes its end, i.e. `..=`
+        inclusive: bool,
+    },
+
+    /// A tuple struct or tuple variant, e.g. `Some(x)`.
+    TupleStruct {
+        /// Path of the struct or variant
+        path: String,
+        /// Element patterns
+        elems: Vec<Pat>,
+    },
+
+    /// A struct or struct variant, e.g. `Point { x, y: 0, .. }`.
+    Struct {
+        /// Path of the struct or variant
+        path: String,
+        /// Field names and patterns
+        fields: Vec<(String, Pat)>,
+        /// Whether remaining fields are ignored with `..`
+        rest: bool,
+    },
+
+    /// A tuple, e.g. `(a, b)`.
+    Tuple(Vec<Pat>),
+
+    /// A slice, e.g. `[first, ..]`.
+    Slice(Vec<Pat>),
+
+    /// A reference, e.g. `&x` or `&mut x`.
+    Ref {
+        /// Whether the reference is mutable
+        mutable: bool,
+        /// Referenced pattern
+        pat: Box<Pat>,
+    },
+
+    /// Alternatives, e.g. `A | B`.
+    Or(Vec<Pat>),
+
+    /// Raw source text.
+    Raw(String),
+}
+
+
+impl Pat {
+    /// Return a new binding pattern.
+    pub fn ident(name: impl Into<String>) -> Self {
+        Pat::Ident {
+            by_ref: false,
+            mutable: false,
+            name: name.into(),
+            sub: None,
+        }
+    }
+
+    /// Return a new path pattern.
+    pub fn path(path: impl Into<String>) -> Self {
+        Pat::Path(path.into())
+    }
+
+    /// Return a new literal pattern. The literal is written verbatim.
+    pub fn lit(lit: impl ToString) -> Self {
+        Pat::Lit(lit.to_string())
+    }
+
+    /// Return a new string literal pattern, escaping the given value.
+    pub fn str(value: impl AsRef<str>) -> Self {
+        Pat::Lit(format!("{:?}", value.as_ref()))
+    }
+
+    /// Return a new inclusive range pattern, `start..=end`.
+    pub fn range(start: impl ToString, end: impl ToString) -> Self {
+        Pat::Range {
+            start: Some(start.to_string()),
+            end: Some(end.to_string()),
+            inclusive: true,
+        }
+    }
+
+    /// Return a new tuple struct pattern.
+    pub fn tuple_struct<I, T>(path: impl Into<String>, elems: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<Pat>,
+    {
+        Pat::TupleStruct {
+            path: path.into(),
+            elems: collect(elems),
+        }
+    }
+
+    /// Return a new struct pattern without fields. Fields are added with
+    /// `Pat::field`.
+    pub fn r#struct(path: impl Into<String>) -> Self {
+        Pat::Struct {
+            path: path.into(),
+            fields: vec![],
+            rest: false,
+        }
+    }
+
+    /// Return a new tuple pattern.
+    pub fn tuple<I, T>(elems: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<Pat>,
+    {
+        Pat::Tuple(collect(elems))
+    }
+
+    /// Return a new slice pattern.
+    pub fn slice<I, T>(elems: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<Pat>,
+    {
+        Pat::Slice(collect(elems))
+    }
+
+    /// Return a new reference pattern.
+    pub fn reference(mutable: bool, pat: impl Into<Pat>) -> Self {
+        Pat::Ref {
+            mutable,
+            pat: Box::new(pat.into()),
+        }
+    }
+
+    /// Return a pattern matching any of the given alternatives.
+    pub fn or<I, T>(alts: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<Pat>,
+    {
+        Pat::Or(collect(alts))
+    }
+
+    /// Add a field to a struct pattern.
+    ///
+    /// A binding with the same name as the field is written in shorthand
+    /// form.
+    ///
+    /// # Panics
+    ///
+    /// The pattern must be a struct pattern.
+    pub fn field(mut self, name: impl Into<String>, pat: impl Into<Pat>) -> Self {
+        match &mut self {
+            Pat::Struct { fields, .. } => fields.push((name.into(), pat.into())),
+            _ => panic!("fields can only be added to struct patterns"),
+        }
+
+        self
+    }
+
+    /// Ignore the remaining fields of a struct pattern with `..`.
+    ///
+    /// # Panics
+    ///
+    /// The pattern must be a struct pattern.
+    pub fn rest(mut self) -> Self {
+        match &mut self {
+            Pat::Struct { rest, .. } => *rest = true,
+            _ => panic!("only struct patterns can ignore remaining fields"),
+        }
+
+        self
+    }
+
+    /// Bind the value matched by this pattern to a name, i.e. `name @ pat`.
+    pub fn bind(self, name: impl Into<String>) -> Self {
+        Pat::Ident {
+            by_ref: false,
+            mutable: false,
+            name: name.into(),
+            sub: Some(Box::new(self)),
+        }
+    }
+
+    /// Formats the pattern using the given formatter.
+    pub fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            Pat::Wild => write!(fmt, "_"),
+            Pat::Rest => write!(fmt, ".."),
+            Pat::Ident { by_ref, mutable, name, sub } => {
+                if *by_ref {
+                    write!(fmt, "ref ")?;
+                }
+
+                if *mutable {
+                    write!(fmt, "mut ")?;
+                }
+
+                write!(fmt, "{}", name)?;
+
+                if let Some(sub) = sub {
+                    write!(fmt, " @ ")?;
+
+                    if let Pat::Or(_) = **sub {
+                        write!(fmt, "(")?;
+                        sub.fmt(fmt)?;
+                        write!(fmt, ")")?;
+                    } else {
+                        sub.fmt(fmt)?;
+                    }
+                }
+
+                Ok(())
+            }
+            Pat::Path(s) => fmt.write_path(s),
+            Pat::Lit(s) | Pat::Raw(s) => write!(fmt, "{}", s),
+            Pat::Range { start, end, inclusive } => {
+                if let Some(start) = start {
+                    write!(fmt, "{}", start)?;
+                }
+
+                write!(fmt, "{}", if *inclusive { "..=" } else { ".." })?;
+
+                if let Some(end) = end {
+                    write!(fmt, "{}", end)?;
+                }
+
+                Ok(())
+            }
+            Pat::TupleStruct { path, elems } => {
+                fmt.write_path(path)?;
+                fmt_list("(", elems, ")", fmt)
+            }
+            Pat::Struct { path, fields, rest } => {
+                fmt.write_path(path)?;
+                write!(fmt, " {{")?;
+
+                for (i, (name, pat)) in fields.iter().enumerate() {
+                    write!(fmt, "{}", if i == 0 { " " } else { ", " })?;
+
+                    match pat {
+                        Pat::Ident { name: bind, sub: None, .. } if bind == name => {
+                            pat.fmt(fmt)?;
+                        }
+                        _ => {
+                            write!(fmt, "{}: ", name)?;
+                            pat.fmt(fmt)?;
+                        }
+                    }
+                }
+
+                if *rest {
+                    write!(fmt, "{}..", if fields.is_empty() { " " } else { ", " })?;
+                }
+
+                if fields.is_empty() && !*rest {
+                    write!(fmt, "}}")
+                } else {
+                    write!(fmt, " }}")
+                }
+            }
+            Pat::Tuple(elems) => {
+                fmt_list("(", elems, "", fmt)?;
+
+                if elems.len() == 1 {
+                    write!(fmt, ",")?;
+                }
+
+                write!(fmt, ")")
+            }
+            Pat::Slice(elems) => fmt_list("[", elems, "]", fmt),
+            Pat::Ref { mutable, pat } => {
+                write!(fmt, "&")?;
+
+                if *mutable {
+                    write!(fmt, "mut ")?;
+                }
+
+                match **pat {
+                    Pat::Or(_) | Pat::Range { .. } => {
+                        write!(fmt, "(")?;
+                        pat.fmt(fmt)?;
+                        write!(fmt, ")")
+                    }
+                    _ => pat.fmt(fmt),
+                }
+            }
+            Pat::Or(alts) => {
+                for (i, alt) in alts.iter().enumerate() {
+                    if i != 0 {
+                        write!(fmt, " | ")?;
+                    }
+                    alt.fmt(fmt)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Converts the string to a raw pattern.
+impl<'a> From<&'a str> for Pat {
+    fn from(src: &'a str) -> Self {
+        Pat::Raw(src.to_string())
+    }
+}
+
+impl From<String> for Pat {
+    fn from(src: String) -> Self {
+        Pat::Raw(src)
+    }
+}
+
+impl<'a> From<&'a String> for Pat {
+    fn from(src: &'a String) -> Self {
+        Pat::Raw(src.clone())
+    }
+}
+
+impl<'a> From<&'a Pat> for Pat {
+    fn from(src: &'a Pat) -> Self {
+        src.clone()
+    }
+}
+
+
+fn collect<I, T>(pats: I) -> Vec<Pat>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<Pat>,
+{
+    pats.into_iter().map(Into::into).collect()
+}
+
+fn fmt_list(open: &str, pats: &[Pat], close: &str, fmt: &mut Formatter) -> fmt::Result {
+    write!(fmt, "{}", open)?;
+
+    for (i, pat) in pats.iter().enumerate() {
+        if i != 0 {
+            write!(fmt, ", ")?;
+        }
+        pat.fmt(fmt)?;
+    }
+
+    write!(fmt, "{}", close)
+}
@@ -1,19 +1,30 @@
 extern crate indexmap;
 
 
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fmt::{self, Write};
+use std::mem;
 
 use indexmap::IndexMap;
 
 use crate::docs::Docs;
-use crate::formatter::{Formatter, Format};
+use crate::formatter::{Formatter, Format, ImportState};
+use crate::extern_block::ExternBlock;
 use crate::function::Function;
-use crate::import::Import;
+use crate::import::{ExternCrate, Import, ImportOrder, PruneImports};
 use crate::item::Item;
+use crate::macro_call::MacroCall;
+use crate::macro_rules::MacroRules;
 use crate::module::Module;
+use crate::type_alias::TypeAlias;
+use crate::union::Union;
+use crate::use_tree::{UseGroup, UseTree};
 use crate::dis_variant::DisVariant;
 use crate::var_def::VarDef;
+use crate::visibility::Visibility;
 use crate::attr::Attr;
+use crate::cfg::Cfg;
 
 use crate::r#enum::Enum;
 use crate::r#impl::Impl;
@@ -22,6 +33,54 @@ use crate::r#trait::Trait;
 use crate::r#type::Type;
 
 
+/// Names of the standard prelude and the paths they refer to. Auto imports
+/// must not shadow them.
+const PRELUDE: &[(&str, &str)] = &[
+    ("AsMut", "std::convert::AsMut"),
+    ("AsRef", "std::convert::AsRef"),
+    ("Box", "std::boxed::Box"),
+    ("Clone", "std::clone::Clone"),
+    ("Copy", "std::marker::Copy"),
+    ("Default", "std::default::Default"),
+    ("DoubleEndedIterator", "std::iter::DoubleEndedIterator"),
+    ("Drop", "std::ops::Drop"),
+    ("Eq", "std::cmp::Eq"),
+    ("Err", "std::result::Result::Err"),
+    ("ExactSizeIterator", "std::iter::ExactSizeIterator"),
+    ("Extend", "std::iter::Extend"),
+    ("Fn", "std::ops::Fn"),
+    ("FnMut", "std::ops::FnMut"),
+    ("FnOnce", "std::ops::FnOnce"),
+    ("From", "std::convert::From"),
+    ("FromIterator", "std::iter::FromIterator"),
+    ("Into", "std::convert::Into"),
+    ("IntoIterator", "std::iter::IntoIterator"),
+    ("Iterator", "std::iter::Iterator"),
+    ("None", "std::option::Option::None"),
+    ("Ok", "std::result::Result::Ok"),
+    ("Option", "std::option::Option"),
+    ("Ord", "std::cmp::Ord"),
+    ("PartialEq", "std::cmp::PartialEq"),
+    ("PartialOrd", "std::cmp::PartialOrd"),
+    ("Result", "std::result::Result"),
+    ("Send", "std::marker::Send"),
+    ("Sized", "std::marker::Sized"),
+    ("Some", "std::option::Option::Some"),
+    ("String", "std::string::String"),
+    ("Sync", "std::marker::Sync"),
+    ("ToOwned", "std::borrow::ToOwned"),
+    ("ToString", "std::string::ToString"),
+    ("TryFrom", "std::convert::TryFrom"),
+    ("TryInto", "std::convert::TryInto"),
+    ("Unpin", "std::marker::Unpin"),
+    ("Vec", "std::vec::Vec"),
+];
+
+
+/// Imports of a scope, grouped by root path.
+type Imports = IndexMap<String, IndexMap<UseTree, Import>>;
+
+
 /// Defines a scope.
 ///
 /// A scope contains modules, types, etc...
@@ -34,7 +93,22 @@ pub struct Scope {
     attrs: Vec<Attr>,
 
     /// Imports
-    imports: IndexMap<String, IndexMap<String, Import>>,
+    imports: Imports,
+
+    /// `extern crate` declarations
+    extern_crates: Vec<ExternCrate>,
+
+    /// Whether qualified type paths are imported automatically, inherited
+    /// from the enclosing scope if unset
+    auto_import: Option<bool>,
+
+    /// Order imports are written in, inherited from the enclosing scope if
+    /// unset
+    import_order: Option<ImportOrder>,
+
+    /// Which references keep imports from being pruned, inherited from the
+    /// enclosing scope if unset
+    prune_imports: Option<PruneImports>,
 
     /// Contents of the documentation,
     items: Vec<Item>,
@@ -47,6 +121,10 @@ impl Scope {
         Scope {
             docs: None,
             imports: IndexMap::new(),
+            extern_crates: vec![],
+            auto_import: None,
+            import_order: None,
+            prune_imports: None,
             items: vec![],
             attrs: vec![],
         }
@@ -70,10 +148,16 @@ impl Scope {
         self.attrs.last_mut().unwrap()
     }
 
+    /// Push a `#[cfg(pred)]` inner attribute, e.g. to gate a whole file.
+    pub fn cfg(&mut self, pred: Cfg) -> &mut Self {
+        self.push_attr(Attr::cfg(pred))
+    }
+
     /// Import a type into the scope.
     ///
     /// This results in a new `use` statement being added to the beginning of
-    /// the scope.
+    /// the scope. `ty` may be a group such as `{A, B}`, and an import nested
+    /// in the path of a use tree is grouped into the tree's statement.
     pub fn import(
         &mut self,
         path: impl Into<String>,
@@ -82,12 +166,85 @@ impl Scope {
         // handle cases where the caller wants to refer to a type namespaced
         // within the containing namespace, like "a::B".
         let path = path.into();
-        let ty = ty.as_ref().split("::").next().unwrap_or(ty.as_ref());
-        self.imports
-            .entry(path.clone())
-            .or_insert(IndexMap::new())
-            .entry(ty.to_string())
-            .or_insert_with(|| Import::new(path, ty))
+        let ty = ty.as_ref();
+        let ty = if ty.starts_with('{') {
+            ty
+        } else {
+            ty.split("::").next().unwrap_or(ty)
+        };
+
+        let import = Import::new(&path, ty);
+        let mut leaves = import.split();
+
+        // A group such as `{A, B}` is kept as a single import, so that its
+        // visibility and attributes apply to all of its names
+        let (root, key) = if leaves.len() == 1 {
+            insert_leaf(&mut self.imports, leaves.pop().unwrap())
+        } else {
+            insert_leaf(&mut self.imports, import)
+        };
+
+        &mut self.imports[&root][&key]
+    }
+
+    /// Set whether qualified type paths, e.g. `std::collections::HashMap`,
+    /// are imported automatically and written by their short name.
+    ///
+    /// Nested modules inherit the setting unless they set it themselves.
+    /// Names that collide with an item or import of the scope are imported
+    /// under an alias such as `IoResult`, or left qualified.
+    pub fn auto_import(&mut self, enabled: bool) -> &mut Self {
+        self.auto_import = Some(enabled);
+        self
+    }
+
+    /// Set the order in which the imports of the scope are written.
+    ///
+    /// Nested modules inherit the order unless they set it themselves.
+    /// Defaults to `ImportOrder::Insertion`.
+    pub fn import_order(&mut self, order: ImportOrder) -> &mut Self {
+        self.import_order = Some(order);
+        self
+    }
+
+    /// Set whether imports that are not referenced by the scope are dropped
+    /// when it is formatted.
+    ///
+    /// Nested modules inherit the setting unless they set it themselves.
+    /// Defaults to `PruneImports::Never`.
+    pub fn prune_imports(&mut self, prune: PruneImports) -> &mut Self {
+        self.prune_imports = Some(prune);
+        self
+    }
+
+    /// Import a use tree into the scope, e.g. `foo::{self, bar::*}`.
+    ///
+    /// Imports are grouped by the first segment of their path, merging
+    /// common prefixes into nested groups.
+    pub fn import_tree(&mut self, tree: impl Into<UseTree>) -> &mut Self {
+        self.push_import(Import::tree(tree))
+    }
+
+    /// Push an import to the scope.
+    pub fn push_import(&mut self, import: Import) -> &mut Self {
+        insert_import(&mut self.imports, import);
+        self
+    }
+
+    /// Declare an external crate, i.e. `extern crate name;`, returning a
+    /// mutable reference to the declaration.
+    pub fn extern_crate(&mut self, name: impl Into<String>) -> &mut ExternCrate {
+        let name = name.into();
+
+        let idx = match self.extern_crates.iter().position(|k| k.name == name) {
+            Some(idx) => idx,
+            None => {
+                self.extern_crates.push(ExternCrate::new(name));
+                self.extern_crates.len() - 1
+            }
+        };
+
+        &mut self.extern_crates[idx]
     }
 
     /// Push a new module definition, returning a mutable reference to it.
@@ -189,6 +346,22 @@ impl Scope {
         self
     }
 
+    /// Push a new union definition, returning a mutable reference to it.
+    pub fn new_union(&mut self, name: impl Into<String>) -> &mut Union {
+        self.push_union(Union::new(name));
+
+        match self.items.last_mut().unwrap() {
+            Item::Union(v) => v,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Push a union definition
+    pub fn push_union(&mut self, item: Union) -> &mut Self {
+        self.items.push(Item::Union(item));
+        self
+    }
+
     /// Push a new function definition, returning a mutable reference to it.
     pub fn new_fn(&mut self, name: impl Into<String>) -> &mut Function {
         self.push_fn(Function::new(name));
@@ -259,6 +432,26 @@ impl Scope {
         self
     }
 
+    /// Push a new type alias, returning a mutable reference to it.
+    pub fn new_type_alias<S, T>(&mut self, name: S, target: T) -> &mut TypeAlias
+    where
+        S: Into<String>,
+        T: Into<Type>,
+    {
+        self.push_type_alias(TypeAlias::new(name, target));
+
+        match self.items.last_mut().unwrap() {
+            Item::TypeAlias(v) => v,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Push a type alias.
+    pub fn push_type_alias(&mut self, item: TypeAlias) -> &mut Self {
+        self.items.push(Item::TypeAlias(item));
+        self
+    }
+
     /// Push a new `impl` block, returning a mutable reference to it.
     pub fn new_impl(&mut self, target: impl Into<Type>) -> &mut Impl {
         self.push_impl(Impl::new(target));
@@ -275,6 +468,56 @@ impl Scope {
         self
     }
 
+    /// Push a new `extern` block with the given ABI, returning a mutable
+    /// reference to it.
+    pub fn new_extern_block(&mut self, abi: impl Into<String>) -> &mut ExternBlock {
+        self.push_extern_block(ExternBlock::new(abi));
+
+        match self.items.last_mut().unwrap() {
+            Item::ExternBlock(v) => v,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Push an `extern` block.
+    pub fn push_extern_block(&mut self, item: ExternBlock) -> &mut Self {
+        self.items.push(Item::ExternBlock(item));
+        self
+    }
+
+    /// Push a new `macro_rules!` definition, returning a mutable reference
+    /// to it.
+    pub fn new_macro_rules(&mut self, name: impl Into<String>) -> &mut MacroRules {
+        self.push_macro_rules(MacroRules::new(name));
+
+        match self.items.last_mut().unwrap() {
+            Item::MacroRules(v) => v,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Push a `macro_rules!` definition.
+    pub fn push_macro_rules(&mut self, item: MacroRules) -> &mut Self {
+        self.items.push(Item::MacroRules(item));
+        self
+    }
+
+    /// Push a new macro invocation, returning a mutable reference to it.
+    pub fn new_macro_call(&mut self, path: impl Into<String>) -> &mut MacroCall {
+        self.push_macro_call(MacroCall::new(path));
+
+        match self.items.last_mut().unwrap() {
+            Item::MacroCall(v) => v,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Push a macro invocation.
+    pub fn push_macro_call(&mut self, item: MacroCall) -> &mut Self {
+        self.items.push(Item::MacroCall(item));
+        self
+    }
+
     /// Push a raw string to the scope.
     ///
     /// This string will be included verbatim in the formatted string.
@@ -297,6 +540,21 @@ impl Scope {
         }
     }
 
+    /// Push a new `static mut` definition, returning a mutable reference
+    /// to it.
+    pub fn new_static_mut<S, T>(&mut self, name: S, ty: T) -> &mut VarDef
+    where
+        S: Into<String>,
+        T: Into<Type>,
+    {
+        self.push_var_def(VarDef::new_static_mut(name, ty));
+
+        match self.items.last_mut().unwrap() {
+            Item::VarDef(v) => v,
+            _ => unreachable!(),
+        }
+    }
+
     /// Push a new struct definition, returning a mutable reference to it.
     pub fn new_const<S, T>(&mut self, name: S, ty: T) -> &mut VarDef
     where
@@ -317,78 +575,12 @@ impl Scope {
         self
     }
 
-    /// Return a string representation of the scope.
-    pub fn to_string(&self) -> String {
-        let mut ret = String::new();
-
-        self.fmt(&mut Formatter::new(&mut ret)).unwrap();
-
-        // Remove the trailing newline
-        if let Some(b'\n') = ret.as_bytes().last() {
-            ret.pop();
-        }
-
-        ret
-    }
-
-    fn fmt_imports(&self, fmt: &mut Formatter) -> fmt::Result {
-        // First, collect all visibilities
-        let mut visibilities = vec![];
-
-        for (_, imports) in &self.imports {
-            for (_, import) in imports {
-                if !visibilities.contains(&import.vis) {
-                    visibilities.push(import.vis.clone());
-                }
-            }
-        }
-
-        let mut tys = vec![];
-
-        // Loop over all visibilities and format the associated imports
-        for vis in &visibilities {
-            for (path, imports) in &self.imports {
-                tys.clear();
-
-                for (ty, import) in imports {
-                    if *vis == import.vis {
-                        tys.push(ty);
-                    }
-                }
-
-                if !tys.is_empty() {
-                    if let Some(vis) = vis {
-                        write!(fmt, "{} ", vis)?;
-                    }
-
-                    write!(fmt, "use {}::", path)?;
-
-                    if tys.len() > 1 {
-                        write!(fmt, "{{")?;
-
-                        for (i, ty) in tys.iter().enumerate() {
-                            if i != 0 {
-                                write!(fmt, ", ")?;
-                            }
-                            write!(fmt, "{}", ty)?;
-                        }
-
-                        writeln!(fmt, "}};")?;
-                    } else if tys.len() == 1 {
-                        writeln!(fmt, "{};", tys[0])?;
-                    }
-                }
-            }
-        }
-
-        Ok(())
-    }
-}
-
-
-impl Format for Scope {
-    /// Formats the scope using the given formatter.
-    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+    /// Formats the docs, attributes, imports and items of the scope.
+    fn fmt_contents(
+        &self,
+        imports: &Imports,
+        fmt: &mut Formatter,
+    ) -> fmt::Result {
         let has_docs;
         if let Some(docs) = &self.docs {
             has_docs = true;
@@ -412,32 +604,50 @@ impl Format for Scope {
         }
 
         let has_imports;
-        if !self.imports.is_empty() {
+        if !imports.is_empty() || !self.extern_crates.is_empty() {
             has_imports = true;
             if has_attrs || has_docs {
                 writeln!(fmt)?;
             }
-            self.fmt_imports(fmt)?;
+            self.fmt_imports(imports, fmt)?;
         } else {
             has_imports = false;
         }
 
-        let mut newline = has_imports || has_attrs || has_docs;
-        for item in &self.items {
-            if newline {
+        if (has_imports || has_attrs || has_docs) && !self.items.is_empty() {
+            writeln!(fmt)?;
+        }
+
+        self.fmt_items(fmt)
+    }
+
+    /// Formats the scope as part of the enclosing one, e.g. the items of a
+    /// macro call, so that its paths are resolved against the imports of the
+    /// enclosing scope.
+    pub(crate) fn fmt_nested(&self, fmt: &mut Formatter) -> fmt::Result {
+        self.fmt_contents(&self.imports, fmt)
+    }
+
+    fn fmt_items(&self, fmt: &mut Formatter) -> fmt::Result {
+        for (i, item) in self.items.iter().enumerate() {
+            if i != 0 {
                 writeln!(fmt)?;
             }
-            newline = true;
 
             match item {
                 Item::Module(v) => v.fmt(fmt)?,
                 Item::Struct(v) => v.fmt(fmt)?,
+                Item::Union(v) => v.fmt(fmt)?,
                 Item::Function(v) => v.fmt(false, fmt)?,
                 Item::Trait(v) => v.fmt(fmt)?,
                 Item::Enum(v) => v.fmt(fmt)?,
                 Item::DisEnum(v) => v.fmt(fmt)?,
+                Item::TypeAlias(v) => v.fmt(fmt)?,
                 Item::Impl(v) => v.fmt(fmt)?,
+                Item::ExternBlock(v) => v.fmt(fmt)?,
                 Item::VarDef(v) => v.fmt(fmt)?,
+                Item::MacroRules(v) => v.fmt(fmt)?,
+                Item::MacroCall(v) => v.fmt(fmt)?,
                 Item::Raw(v) => {
                     write!(fmt, "{}", v)?;
                 }
@@ -448,4 +658,505 @@ impl Format for Scope {
 
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// Collects the qualified type paths written by the items of the scope
+    /// and imports them.
+    ///
+    /// Returns the imports of the scope including the added ones, and the
+    /// names the qualified paths are written as. A path whose name is
+    /// already taken, by an item, import, prelude name, generic parameter or
+    /// unqualified path of the scope, is imported under an alias prefixed by
+    /// its parent module, e.g. `IoResult`, or left qualified if that is
+    /// taken as well.
+    fn resolve_imports(
+        &self,
+        fmt: &Formatter,
+    ) -> Result<(Imports, HashMap<String, String>), fmt::Error> {
+        let mut dst = String::new();
+        let mut dry = fmt.scratch(&mut dst);
+        dry.imports.auto_import = true;
+        dry.imports.seen = Some(vec![]);
+        dry.imports.used = Some(HashSet::new());
+
+        self.fmt_items(&mut dry)?;
+
+        let seen = dry.imports.seen.take().unwrap_or_default();
+        let used = dry.imports.used.take().unwrap_or_default();
+
+        // Names in scope, mapped to the path they refer to if imported
+        let mut taken: HashMap<String, Option<String>> = HashMap::new();
+
+        // Prelude paths are written by their prelude name, unless an item
+        // or import of the scope shadows it
+        for (name, path) in PRELUDE {
+            taken.insert(name.to_string(), Some(path.to_string()));
+        }
+
+        for name in used {
+            taken.entry(name).or_insert(None);
+        }
+
+        for name in self.declared_names() {
+            taken.insert(name, None);
+        }
+
+        for import in self.imports.values().flat_map(|leaves| leaves.values()) {
+            for (path, name) in import.bindings() {
+                if name != "*" {
+                    taken.insert(name, Some(path));
+                }
+            }
+        }
+
+        let mut imports = self.imports.clone();
+        let mut names = HashMap::new();
+
+        for path in seen {
+            let (parent, last) = path.rsplit_once("::").unwrap();
+
+            if parent.trim_start_matches("::").is_empty() {
+                continue;
+            }
+
+            let existing = taken.iter().find(|(_, p)| p.as_ref() == Some(&path));
+
+            if let Some((name, _)) = existing {
+                names.insert(path.clone(), name.clone());
+                continue;
+            }
+
+            let module = parent.rsplit("::").next().unwrap();
+            let alias = format!("{}{}", camel_case(module), last);
+
+            for name in [last.to_string(), alias] {
+                if taken.contains_key(&name) {
+                    continue;
+                }
+
+                let tree = if name == last {
+                    UseTree::name(last)
+                } else {
+                    UseTree::rename(last, name.clone())
+                };
+
+                insert_import(&mut imports, Import {
+                    path: parent.to_string(),
+                    tree,
+                    vis: Visibility::Private,
+                    attrs: vec![],
+                    keep: false,
+                    grouped: false,
+                });
+
+                taken.insert(name.clone(), Some(path.clone()));
+                names.insert(path.clone(), name);
+                break;
+            }
+        }
+
+        Ok((imports, names))
+    }
+
+    /// Formats the items as a dry run and returns the names they refer to,
+    /// which keep imports from being pruned.
+    fn used_names(
+        &self,
+        prune: PruneImports,
+        names: &HashMap<String, String>,
+        fmt: &Formatter,
+    ) -> Result<HashSet<String>, fmt::Error> {
+        let mut dst = String::new();
+
+        let mut used = {
+            let mut dry = fmt.scratch(&mut dst);
+            dry.imports.names = names.clone();
+            dry.imports.used = Some(HashSet::new());
+
+            self.fmt_items(&mut dry)?;
+
+            dry.imports.used.take().unwrap_or_default()
+        };
+
+        if prune == PruneImports::TypesAndTokens {
+            let tokens = dst
+                .split(|c: char| c != '_' && !c.is_alphanumeric())
+                .filter(|token| !token.is_empty());
+
+            used.extend(tokens.map(String::from));
+        }
+
+        Ok(used)
+    }
+
+    /// Returns the features referenced by the `cfg` and `cfg_attr`
+    /// attributes of the scope, its items and nested modules, sorted and
+    /// without duplicates.
+    ///
+    /// The attributes are collected by formatting the scope, including
+    /// statements in fn bodies and imports that are not pruned, and the
+    /// output is discarded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the scope cannot be formatted, in the same cases as
+    /// `Scope::to_string`, e.g. an impl fn without a body.
+    pub fn features(&self) -> Vec<String> {
+        let mut dst = String::new();
+        let mut dry = Formatter::new(&mut dst);
+        dry.features = Some(BTreeSet::new());
+
+        Format::fmt(self, &mut dry).expect("failed to format the scope");
+
+        dry.features.take().unwrap_or_default().into_iter().collect()
+    }
+
+    /// Returns the names of the modules, types, traits, functions, constants
+    /// and statics of the scope that are declared `pub`.
+    pub fn public_names(&self) -> Vec<&str> {
+        self.items
+            .iter()
+            .filter_map(|item| {
+                let (ty, vis) = match item {
+                    Item::Module(v) => return Some((&v.name[..], &v.vis)),
+                    Item::Function(v) => return Some((v.name(), v.visibility())),
+                    Item::VarDef(v) => return Some((&v.name[..], &v.vis)),
+                    Item::Struct(v) => (v.ty(), v.visibility()),
+                    Item::Union(v) => (v.ty(), v.visibility()),
+                    Item::Trait(v) => (v.ty(), v.visibility()),
+                    Item::Enum(v) => (v.ty(), v.visibility()),
+                    Item::DisEnum(v) => (v.ty(), v.visibility()),
+                    Item::TypeAlias(v) => (v.ty(), v.visibility()),
+                    _ => return None,
+                };
+
+                match ty {
+                    Type::Path { name, .. } => Some((&name[..], vis)),
+                    _ => None,
+                }
+            })
+            .filter(|(_, vis)| vis.is_pub())
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    /// Returns the names of the types, traits and modules defined in the
+    /// scope.
+    fn declared_names(&self) -> Vec<String> {
+        self.items
+            .iter()
+            .filter_map(|item| {
+                let ty = match item {
+                    Item::Module(v) => return Some(v.name.clone()),
+                    Item::Struct(v) => v.ty(),
+                    Item::Union(v) => v.ty(),
+                    Item::Trait(v) => v.ty(),
+                    Item::Enum(v) => v.ty(),
+                    Item::DisEnum(v) => v.ty(),
+                    Item::TypeAlias(v) => v.ty(),
+                    _ => return None,
+                };
+
+                match ty {
+                    Type::Path { name, .. } => Some(name.clone()),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    fn fmt_imports(
+        &self,
+        imports: &Imports,
+        fmt: &mut Formatter,
+    ) -> fmt::Result {
+        let order = fmt.imports.order;
+
+        let mut extern_crates: Vec<_> = self.extern_crates.iter().collect();
+
+        if order != ImportOrder::Insertion {
+            extern_crates.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+
+        for krate in extern_crates {
+            for attr in &krate.attrs {
+                attr.fmt(fmt, false)?;
+            }
+
+            if !krate.vis.is_private() {
+                write!(fmt, "{} ", krate.vis)?;
+            }
+
+            write!(fmt, "extern crate {}", krate.name)?;
+
+            if let Some(alias) = &krate.alias {
+                write!(fmt, " as {}", alias)?;
+            }
+
+            writeln!(fmt, ";")?;
+        }
+
+        // First, collect all visibilities and attributes, as imports can
+        // only be grouped if they have the same ones
+        let mut kinds = vec![];
+
+        for imports in imports.values() {
+            for import in imports.values() {
+                let kind = (&import.vis, &import.attrs);
+
+                if !kinds.contains(&kind) {
+                    kinds.push(kind);
+                }
+            }
+        }
+
+        // Loop over all kinds and collect the associated imports
+        let mut lines = vec![];
+
+        for &(vis, attrs) in &kinds {
+            for (path, leaves) in imports {
+                let mut group = UseGroup::default();
+
+                for import in leaves.values() {
+                    if *vis == import.vis && *attrs == import.attrs {
+                        for (segs, leaf) in import.tree.leaves() {
+                            group.insert(&segs, &leaf);
+                        }
+                    }
+                }
+
+                if !group.is_empty() {
+                    lines.push((vis, attrs, path, group));
+                }
+            }
+        }
+
+        if order != ImportOrder::Insertion {
+            for (_, _, _, group) in &mut lines {
+                group.sort();
+            }
+
+            lines.sort_by(|(vis_a, _, path_a, _), (vis_b, _, path_b, _)| {
+                let key_a = (path_a.trim_start_matches("::"), vis_a);
+                let key_b = (path_b.trim_start_matches("::"), vis_b);
+                key_a.cmp(&key_b)
+            });
+        }
+
+        if order == ImportOrder::Grouped {
+            lines.sort_by_key(|(_, _, path, _)| ImportOrder::group(path));
+        }
+
+        for (i, (vis, attrs, path, group)) in lines.iter().enumerate() {
+            if order == ImportOrder::Grouped && i != 0 {
+                let prev = ImportOrder::group(lines[i - 1].2);
+
+                if prev != ImportOrder::group(path) {
+                    writeln!(fmt)?;
+                }
+            }
+
+            for attr in attrs.iter() {
+                attr.fmt(fmt, false)?;
+            }
+
+            if !vis.is_private() {
+                write!(fmt, "{} ", vis)?;
+            }
+
+            write!(fmt, "use ")?;
+            group.fmt_with_prefix(path, fmt)?;
+            writeln!(fmt, ";")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Scope {
+    fn default() -> Self {
+        Scope::new()
+    }
+}
+
+
+impl fmt::Display for Scope {
+    /// Writes the string representation of the scope.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut ret = String::new();
+
+        Format::fmt(self, &mut Formatter::new(&mut ret))?;
+
+        // Remove the trailing newline
+        if let Some(b'\n') = ret.as_bytes().last() {
+            ret.pop();
+        }
+
+        f.write_str(&ret)
+    }
+}
+
+
+impl Format for Scope {
+    /// Formats the scope using the given formatter.
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        let auto_import = self.auto_import.unwrap_or(fmt.imports.auto_import);
+
+        let order = self.import_order.unwrap_or(fmt.imports.order);
+        let prune = self.prune_imports.unwrap_or(fmt.imports.prune);
+
+        let mut state = ImportState {
+            auto_import,
+            order,
+            prune,
+            ..ImportState::default()
+        };
+
+        // The imports of nested scopes do not affect the output of a dry
+        // run, so they are only resolved and pruned for the actual output
+        let mut imports = if auto_import && !fmt.dry_run {
+            let (imports, names) = self.resolve_imports(fmt)?;
+            state.names = names;
+            Cow::Owned(imports)
+        } else {
+            Cow::Borrowed(&self.imports)
+        };
+
+        if prune != PruneImports::Never && !fmt.dry_run {
+            let used = self.used_names(prune, &state.names, fmt)?;
+
+            let imports = imports.to_mut();
+
+            for leaves in imports.values_mut() {
+                leaves.retain(|_, import| {
+                    import.keep
+                        || !import.vis.is_private()
+                        || import.bindings().iter().any(|(_, name)| {
+                            name == "*" || name == "_" || used.contains(name)
+                        })
+                });
+            }
+
+            imports.retain(|_, leaves| !leaves.is_empty());
+        }
+
+        // Paths written by this scope are resolved against its own imports
+        let outer = mem::replace(&mut fmt.imports, state);
+        let ret = self.fmt_contents(&imports, fmt);
+        fmt.imports = outer;
+
+        ret
+    }
+}
+
+
+/// Inserts each leaf of the import, grouped by its root path.
+fn insert_import(imports: &mut Imports, import: Import) {
+    for leaf in import.split() {
+        insert_leaf(imports, leaf);
+    }
+}
+
+/// Inserts the leaf, returning the root and key it is stored under.
+///
+/// A leaf importing a path under a name already imported by the scope is
+/// skipped. A leaf whose path is nested in the root of a use tree, e.g.
+/// `std::fmt` in `std`, is grouped under that root, and the leaf of a use
+/// tree absorbs the existing roots nested in its path.
+fn insert_leaf(imports: &mut Imports, leaf: Import) -> (String, UseTree) {
+    if leaf.tree.leaves().len() == 1 {
+        if let Some(found) = find_binding(imports, &leaf) {
+            return found;
+        }
+    }
+
+    let outer = imports
+        .iter()
+        .find(|(root, leaves)| {
+            is_nested(&leaf.path, root) && leaves.values().any(|import| import.grouped)
+        })
+        .map(|(root, _)| root.clone());
+
+    let leaf = match outer {
+        Some(root) => rebase(leaf, &root),
+        None if leaf.grouped => {
+            let nested: Vec<String> = imports
+                .keys()
+                .filter(|root| is_nested(root, &leaf.path))
+                .cloned()
+                .collect();
+
+            for root in nested {
+                for (_, import) in imports.shift_remove(&root).unwrap() {
+                    let import = rebase(import, &leaf.path);
+
+                    imports
+                        .entry(leaf.path.clone())
+                        .or_default()
+                        .entry(import.tree.clone())
+                        .or_insert(import);
+                }
+            }
+
+            leaf
+        }
+        None => leaf,
+    };
+
+    let root = leaf.path.clone();
+    let key = leaf.tree.clone();
+
+    imports
+        .entry(root.clone())
+        .or_default()
+        .entry(key.clone())
+        .or_insert(leaf);
+
+    (root, key)
+}
+
+/// Returns true if `path` is nested in the root path, e.g. `std::fmt` in
+/// `std`.
+fn is_nested(path: &str, root: &str) -> bool {
+    !root.trim_start_matches("::").is_empty()
+        && path.starts_with(root)
+        && path[root.len()..].starts_with("::")
+}
+
+/// Moves the import under a root its path is nested in.
+fn rebase(import: Import, root: &str) -> Import {
+    let rest = import.path[root.len() + 2..].to_string();
+
+    Import {
+        tree: UseTree::path(rest, import.tree),
+        path: root.to_string(),
+        grouped: true,
+        ..import
+    }
+}
+
+/// Returns the root and key of the import binding the same path under the
+/// same name as the given leaf, if any.
+fn find_binding(imports: &Imports, leaf: &Import) -> Option<(String, UseTree)> {
+    let binding = leaf.binding();
+
+    imports.iter().find_map(|(root, leaves)| {
+        leaves
+            .iter()
+            .find(|(_, import)| import.bindings().contains(&binding))
+            .map(|(key, _)| (root.clone(), key.clone()))
+    })
+}
+
+/// Converts a `snake_case` module name to `CamelCase`.
+fn camel_case(name: &str) -> String {
+    name.split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+
+            match chars.next() {
+                Some(c) => c.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,170 @@
+use std::fmt::{self, Write};
+
+use crate::attr::Attr;
+use crate::cfg::Cfg;
+use crate::expr::Expr;
+use crate::formatter::{Formatter, Format};
+use crate::macro_call::MacroCall;
+
+use crate::r#type::Type;
+
+
+/// Defines a statement.
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub enum Stmt {
+    /// A `let` binding.
+    Let(Let),
+
+    /// An expression without a trailing semicolon, e.g. the tail expression
+    /// of a block or a block-like expression such as `if`.
+    Expr(Expr),
+
+    /// An expression followed by a semicolon.
+    Semi(Expr),
+
+    /// A macro invocation, followed by a semicolon unless its tokens are
+    /// delimited by braces.
+    Macro(MacroCall),
+
+    /// A statement preceded by outer attributes, e.g.
+    /// `#[allow(unused_mut)] let mut x = 1;`.
+    Attributed(Vec<Attr>, Box<Stmt>),
+}
+
+
+/// Defines a `let` binding, e.g. `let mut x: u32 = 1;`.
+#[derive(Debug, Clone)]
+pub struct Let {
+    pat: String,
+    mutable: bool,
+    ty: Option<Type>,
+    init: Option<Expr>,
+}
+
+
+impl Stmt {
+    /// Return a new expression statement without a trailing semicolon.
+    pub fn expr(expr: impl Into<Expr>) -> Self {
+        Stmt::Expr(expr.into())
+    }
+
+    /// Return a new expression statement with a trailing semicolon.
+    pub fn semi(expr: impl Into<Expr>) -> Self {
+        Stmt::Semi(expr.into())
+    }
+
+    /// Add an outer attribute to the statement.
+    pub fn attr(self, attr: Attr) -> Self {
+        match self {
+            Stmt::Attributed(mut attrs, stmt) => {
+                attrs.push(attr);
+                Stmt::Attributed(attrs, stmt)
+            }
+            stmt => Stmt::Attributed(vec![attr], Box::new(stmt)),
+        }
+    }
+
+    /// Add a `#[cfg(pred)]` attribute to the statement.
+    pub fn cfg(self, pred: Cfg) -> Self {
+        self.attr(Attr::cfg(pred))
+    }
+}
+
+impl From<Let> for Stmt {
+    fn from(src: Let) -> Self {
+        Stmt::Let(src)
+    }
+}
+
+impl From<MacroCall> for Stmt {
+    fn from(src: MacroCall) -> Self {
+        Stmt::Macro(src)
+    }
+}
+
+
+impl Format for Stmt {
+    /// Formats the statement using the given formatter.
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            Stmt::Let(v) => v.fmt(fmt),
+            Stmt::Expr(v) => {
+                v.fmt(fmt)?;
+                writeln!(fmt)
+            }
+            Stmt::Semi(v) => {
+                v.fmt(fmt)?;
+                writeln!(fmt, ";")
+            }
+            Stmt::Macro(v) => {
+                v.fmt(fmt)?;
+                writeln!(fmt)
+            }
+            Stmt::Attributed(attrs, stmt) => {
+                for attr in attrs {
+                    attr.fmt(fmt, false)?;
+                }
+
+                stmt.fmt(fmt)
+            }
+        }
+    }
+}
+
+
+impl Let {
+    /// Return a new `let` binding of the given pattern.
+    pub fn new(pat: impl Into<String>) -> Self {
+        Let {
+            pat: pat.into(),
+            mutable: false,
+            ty: None,
+            init: None,
+        }
+    }
+
+    /// Set whether the binding is mutable.
+    pub fn set_mut(&mut self, mutable: bool) -> &mut Self {
+        self.mutable = mutable;
+        self
+    }
+
+    /// Set the type annotation.
+    pub fn ty(&mut self, ty: impl Into<Type>) -> &mut Self {
+        self.ty = Some(ty.into());
+        self
+    }
+
+    /// Set the initializer.
+    pub fn init(&mut self, expr: impl Into<Expr>) -> &mut Self {
+        self.init = Some(expr.into());
+        self
+    }
+}
+
+
+impl Format for Let {
+    /// Formats the binding using the given formatter.
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        write!(fmt, "let ")?;
+
+        if self.mutable {
+            write!(fmt, "mut ")?;
+        }
+
+        write!(fmt, "{}", self.pat)?;
+
+        if let Some(ty) = &self.ty {
+            write!(fmt, ": ")?;
+            ty.fmt(fmt)?;
+        }
+
+        if let Some(init) = &self.init {
+            write!(fmt, " = ")?;
+            init.fmt(fmt)?;
+        }
+
+        writeln!(fmt, ";")
+    }
+}
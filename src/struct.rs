@@ -1,9 +1,14 @@
 use std::fmt::{self, Write};
 
+use crate::attr::Attr;
+use crate::cfg::Cfg;
 use crate::field::Field;
 use crate::fields::Fields;
 use crate::formatter::{Formatter, Format};
+use crate::generic_param::GenericParam;
 use crate::type_def::TypeDef;
+use crate::visibility::Visibility;
+use crate::where_predicate::WherePredicate;
 
 use crate::r#type::Type;
 
@@ -32,28 +37,72 @@ impl Struct {
         &self.type_def.ty
     }
 
+    /// Returns the visibility.
+    pub fn visibility(&self) -> &Visibility {
+        self.type_def.visibility()
+    }
+
     /// Set the structure visibility.
-    pub fn vis(&mut self, vis: impl Into<String>) -> &mut Self {
+    pub fn vis(&mut self, vis: impl Into<Visibility>) -> &mut Self {
         self.type_def.vis(vis);
         self
     }
 
     /// Add a generic to the struct.
-    pub fn generic(&mut self, name: impl Into<Type>) -> &mut Self {
-        self.type_def.ty.generic(name);
+    pub fn generic(&mut self, param: impl Into<GenericParam>) -> &mut Self {
+        self.type_def.generic(param.into());
         self
     }
 
     /// Add a `where` bound to the struct.
     pub fn bound<S, T>(&mut self, name: S, ty: T) -> &mut Self
     where
-        S: Into<String>,
+        S: Into<Type>,
         T: Into<Type>,
     {
         self.type_def.bound(name, ty);
         self
     }
 
+    /// Push a `where` predicate to the struct.
+    ///
+    /// Bounds on a type that is already constrained are merged into the
+    /// existing predicate.
+    pub fn push_predicate(&mut self, pred: WherePredicate) -> &mut Self {
+        self.type_def.push_predicate(pred);
+        self
+    }
+
+    /// Returns the `where` predicate on the given type, creating it if it
+    /// does not exist.
+    pub fn new_predicate<T>(&mut self, bounded: T) -> &mut WherePredicate
+    where
+        T: Into<Type>,
+    {
+        self.type_def.new_predicate(bounded)
+    }
+
+    /// Push an outer attribute.
+    pub fn push_attr(&mut self, attr: Attr) -> &mut Self {
+        self.type_def.push_attr(attr);
+        self
+    }
+
+    /// Create an outer attribute.
+    pub fn new_attr(&mut self, name: impl Into<String>) -> &mut Attr {
+        self.type_def.new_attr(name)
+    }
+
+    /// Push a `#[cfg(pred)]` attribute.
+    pub fn cfg(&mut self, pred: Cfg) -> &mut Self {
+        self.push_attr(Attr::cfg(pred))
+    }
+
+    /// Push a `#[cfg_attr(pred, attr)]` attribute.
+    pub fn cfg_attr(&mut self, pred: Cfg, attr: Attr) -> &mut Self {
+        self.push_attr(Attr::cfg_attr(pred, attr))
+    }
+
     /// Set the structure documentation.
     pub fn doc(&mut self, docs: impl Into<String>) -> &mut Self {
         self.type_def.doc(docs);
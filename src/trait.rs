@@ -1,10 +1,16 @@
 use std::fmt::{self, Write};
 
 use crate::assoc_type::AssociatedType;
+use crate::attr::Attr;
+use crate::cfg::Cfg;
 use crate::bound::Bound;
 use crate::formatter::{Formatter, Format, fmt_bound_rhs};
 use crate::function::Function;
+use crate::generic_param::GenericParam;
 use crate::type_def::TypeDef;
+use crate::var_def::VarDef;
+use crate::visibility::Visibility;
+use crate::where_predicate::WherePredicate;
 
 use crate::r#type::Type;
 
@@ -15,8 +21,8 @@ pub struct Trait {
     type_def: TypeDef,
     parents: Vec<Type>,
     associated_tys: Vec<AssociatedType>,
+    consts: Vec<VarDef>,
     fns: Vec<Function>,
-    macros: Vec<String>,
 }
 
 
@@ -27,8 +33,8 @@ impl Trait {
             type_def: TypeDef::new(name),
             parents: vec![],
             associated_tys: vec![],
+            consts: vec![],
             fns: vec![],
-            macros: vec![],
         }
     }
 
@@ -37,20 +43,25 @@ impl Trait {
         &self.type_def.ty
     }
 
+    /// Returns the visibility.
+    pub fn visibility(&self) -> &Visibility {
+        self.type_def.visibility()
+    }
+
     /// Set the trait visibility.
-    pub fn vis(&mut self, vis: impl Into<String>) -> &mut Self {
+    pub fn vis(&mut self, vis: impl Into<Visibility>) -> &mut Self {
         self.type_def.vis(vis);
         self
     }
 
     /// Add a generic to the trait
-    pub fn generic(&mut self, name: impl Into<Type>) -> &mut Self {
-        self.type_def.ty.generic(name);
+    pub fn generic(&mut self, param: impl Into<GenericParam>) -> &mut Self {
+        self.type_def.generic(param.into());
         self
     }
 
     /// Add a `where` bound to the trait.
-    pub fn bound<T>(&mut self, name: impl Into<String>, ty: T) -> &mut Self
+    pub fn bound<T>(&mut self, name: impl Into<Type>, ty: T) -> &mut Self
     where
         T: Into<Type>,
     {
@@ -58,6 +69,24 @@ impl Trait {
         self
     }
 
+    /// Push a `where` predicate to the trait.
+    ///
+    /// Bounds on a type that is already constrained are merged into the
+    /// existing predicate.
+    pub fn push_predicate(&mut self, pred: WherePredicate) -> &mut Self {
+        self.type_def.push_predicate(pred);
+        self
+    }
+
+    /// Returns the `where` predicate on the given type, creating it if it
+    /// does not exist.
+    pub fn new_predicate<T>(&mut self, bounded: T) -> &mut WherePredicate
+    where
+        T: Into<Type>,
+    {
+        self.type_def.new_predicate(bounded)
+    }
+
     /// Add a macro to the trait def (e.g. `"#[async_trait]"`)
     pub fn r#macro(&mut self, r#macro: impl Into<String>) -> &mut Self {
         self.type_def.r#macro(r#macro);
@@ -73,6 +102,27 @@ impl Trait {
         self
     }
 
+    /// Push an outer attribute.
+    pub fn push_attr(&mut self, attr: Attr) -> &mut Self {
+        self.type_def.push_attr(attr);
+        self
+    }
+
+    /// Create an outer attribute.
+    pub fn new_attr(&mut self, name: impl Into<String>) -> &mut Attr {
+        self.type_def.new_attr(name)
+    }
+
+    /// Push a `#[cfg(pred)]` attribute.
+    pub fn cfg(&mut self, pred: Cfg) -> &mut Self {
+        self.push_attr(Attr::cfg(pred))
+    }
+
+    /// Push a `#[cfg_attr(pred, attr)]` attribute.
+    pub fn cfg_attr(&mut self, pred: Cfg, attr: Attr) -> &mut Self {
+        self.push_attr(Attr::cfg_attr(pred, attr))
+    }
+
     /// Set the trait documentation.
     pub fn doc(&mut self, docs: impl Into<String>) -> &mut Self {
         self.type_def.doc(docs);
@@ -90,7 +140,27 @@ impl Trait {
         self.associated_tys.last_mut().unwrap()
     }
 
-    /// Push a new function definition, returning a mutable reference to it.
+    /// Push a new associated constant, returning a mutable reference to it.
+    ///
+    /// The constant is required unless a default is set with
+    /// `VarDef::value`.
+    pub fn new_const<S, T>(&mut self, name: S, ty: T) -> &mut VarDef
+    where
+        S: Into<String>,
+        T: Into<Type>,
+    {
+        self.push_const(VarDef::new_const(name, ty));
+        self.consts.last_mut().unwrap()
+    }
+
+    /// Push an associated constant, with an optional default value.
+    pub fn push_const(&mut self, item: VarDef) -> &mut Self {
+        self.consts.push(item);
+        self
+    }
+
+    /// Push a new required function, i.e. one without a body, returning a
+    /// mutable reference to it.
     pub fn new_fn(&mut self, name: impl Into<String>) -> &mut Function {
         let mut func = Function::new(name);
         func.body = None;
@@ -99,11 +169,37 @@ impl Trait {
         self.fns.last_mut().unwrap()
     }
 
-    /// Push a function definition.
+    /// Push a new provided function, i.e. one with a default body,
+    /// returning a mutable reference to it.
+    pub fn new_provided_fn(&mut self, name: impl Into<String>) -> &mut Function {
+        self.push_fn(Function::new(name));
+        self.fns.last_mut().unwrap()
+    }
+
+    /// Push a function definition. The function is provided if it has a
+    /// body, and required otherwise.
     pub fn push_fn(&mut self, item: Function) -> &mut Self {
         self.fns.push(item);
         self
     }
+
+    /// Returns the names of the functions that implementors must define.
+    pub fn required_fns(&self) -> Vec<&str> {
+        self.fns
+            .iter()
+            .filter(|func| func.body.is_none())
+            .map(|func| func.name())
+            .collect()
+    }
+
+    /// Returns the names of the functions with a default body.
+    pub fn provided_fns(&self) -> Vec<&str> {
+        self.fns
+            .iter()
+            .filter(|func| func.body.is_some())
+            .map(|func| func.name())
+            .collect()
+    }
 }
 
 
@@ -131,7 +227,16 @@ impl Format for Trait {
                 }
             }
 
-            let mut newline = !assoc.is_empty();
+            if !assoc.is_empty() && !self.consts.is_empty() {
+                writeln!(fmt)?;
+            }
+
+            // format associated constants
+            for var in &self.consts {
+                var.fmt_assoc(true, fmt)?;
+            }
+
+            let mut newline = !assoc.is_empty() || !self.consts.is_empty();
             for func in &self.fns {
                 if newline {
                     writeln!(fmt)?;
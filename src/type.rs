@@ -1,74 +1,471 @@
 use std::fmt::{self, Write};
 
-use crate::formatter::Formatter;
+use crate::formatter::{Formatter, fmt_bound_rhs};
+use crate::parse::{ParseError, Parser};
 
 
 /// Defines a type.
-#[derive(Debug, Clone)]
-pub struct Type {
-    name: String,
-    generics: Vec<Type>,
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Type {
+    /// A named type with optional generic arguments, e.g. `std::vec::Vec<T>`.
+    Path {
+        /// Path of the type, segments separated by `::`
+        name: String,
+        /// Generic arguments
+        generics: Vec<Type>,
+    },
+
+    /// A lifetime, e.g. `'a`.
+    ///
+    /// Lifetimes are only valid as generic arguments and trait object bounds.
+    Lifetime(String),
+
+    /// An associated type binding, e.g. the `Item = u32` in
+    /// `Iterator<Item = u32>`.
+    ///
+    /// Bindings are only valid as generic arguments.
+    Binding {
+        /// Associated type name
+        name: String,
+        /// Bound type
+        ty: Box<Type>,
+    },
+
+    /// A reference, e.g. `&'a mut T`.
+    Reference {
+        /// Optional lifetime, including the leading `'`
+        lifetime: Option<String>,
+        /// Whether the reference is mutable
+        mutable: bool,
+        /// Referenced type
+        inner: Box<Type>,
+    },
+
+    /// A raw pointer, e.g. `*const T`.
+    Ptr {
+        /// Whether the pointer is `*mut` rather than `*const`
+        mutable: bool,
+        /// Pointee type
+        inner: Box<Type>,
+    },
+
+    /// A tuple, e.g. `(A, B)`. The empty tuple is the unit type.
+    Tuple(Vec<Type>),
+
+    /// An array, e.g. `[u8; 4]`.
+    Array {
+        /// Element type
+        inner: Box<Type>,
+        /// Length expression
+        len: String,
+    },
+
+    /// A slice, e.g. `[u8]`.
+    Slice(Box<Type>),
+
+    /// A function pointer, e.g. `fn(u32) -> bool`.
+    FnPtr {
+        /// Parameter types
+        params: Vec<Type>,
+        /// Return type
+        ret: Option<Box<Type>>,
+    },
+
+    /// One of the `Fn` family of traits with parenthesized arguments, e.g.
+    /// `FnMut(u32) -> bool`.
+    FnTrait {
+        /// Trait name, e.g. `FnOnce`
+        name: String,
+        /// Parameter types
+        params: Vec<Type>,
+        /// Return type
+        ret: Option<Box<Type>>,
+    },
+
+    /// A trait object, e.g. `dyn Fn() + Send`.
+    DynTrait(Vec<Type>),
+
+    /// An anonymous type implementing the given bounds, e.g.
+    /// `impl Iterator<Item = u32>`.
+    ImplTrait(Vec<Type>),
+
+    /// A qualified path, e.g. `<T as Trait>::Assoc`.
+    QPath {
+        /// The self type
+        qself: Box<Type>,
+        /// The trait the self type is cast to
+        as_trait: Option<Box<Type>>,
+        /// Path following the qualified self type
+        name: String,
+    },
 }
 
 
 impl Type {
     /// Return a new type with the given name.
     pub fn new(name: impl Into<String>) -> Self {
-        Type {
+        Type::Path {
             name: name.into(),
             generics: vec![],
         }
     }
 
+    /// Parse a type from a string.
+    ///
+    /// Paths, generic arguments, references, raw pointers, tuples, arrays,
+    /// slices, fn pointers, trait objects and qualified paths are
+    /// understood.
+    ///
+    /// ```
+    /// use codegen::Type;
+    ///
+    /// let mut ty = Type::parse("std::collections::HashMap<K, Vec<u8>>").unwrap();
+    /// ```
+    pub fn parse(src: &str) -> Result<Type, ParseError> {
+        let mut parser = Parser::new(src);
+        let ty = parser.parse_type()?;
+        parser.finish()?;
+        Ok(ty)
+    }
+
+    /// Return a new lifetime. The leading `'` is optional.
+    pub fn lifetime(name: impl Into<String>) -> Self {
+        let mut name = name.into();
+
+        if !name.starts_with('\'') {
+            name.insert(0, '\'');
+        }
+
+        Type::Lifetime(name)
+    }
+
+    /// Return a new associated type binding, e.g. `Item = u32`.
+    pub fn binding<S, T>(name: S, ty: T) -> Self
+    where
+        S: Into<String>,
+        T: Into<Type>,
+    {
+        Type::Binding {
+            name: name.into(),
+            ty: Box::new(ty.into()),
+        }
+    }
+
+    /// Return a new reference type.
+    ///
+    /// ```
+    /// use codegen::Type;
+    ///
+    /// // `&'a mut T`
+    /// let ty = Type::reference(Some("'a"), true, "T");
+    /// ```
+    pub fn reference<T>(lifetime: Option<&str>, mutable: bool, inner: T) -> Self
+    where
+        T: Into<Type>,
+    {
+        Type::Reference {
+            lifetime: lifetime.map(|lt| match Type::lifetime(lt) {
+                Type::Lifetime(lt) => lt,
+                _ => unreachable!(),
+            }),
+            mutable,
+            inner: Box::new(inner.into()),
+        }
+    }
+
+    /// Return a new raw pointer type.
+    pub fn ptr<T>(mutable: bool, inner: T) -> Self
+    where
+        T: Into<Type>,
+    {
+        Type::Ptr {
+            mutable,
+            inner: Box::new(inner.into()),
+        }
+    }
+
+    /// Return a new tuple type.
+    pub fn tuple<I, T>(tys: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<Type>,
+    {
+        Type::Tuple(tys.into_iter().map(Into::into).collect())
+    }
+
+    /// Return the unit type, `()`.
+    pub fn unit() -> Self {
+        Type::Tuple(vec![])
+    }
+
+    /// Return a new array type with the given length expression.
+    pub fn array<T>(inner: T, len: impl ToString) -> Self
+    where
+        T: Into<Type>,
+    {
+        Type::Array {
+            inner: Box::new(inner.into()),
+            len: len.to_string(),
+        }
+    }
+
+    /// Return a new slice type.
+    pub fn slice<T>(inner: T) -> Self
+    where
+        T: Into<Type>,
+    {
+        Type::Slice(Box::new(inner.into()))
+    }
+
+    /// Return a new function pointer type.
+    ///
+    /// ```
+    /// use codegen::Type;
+    ///
+    /// // `fn(u32) -> bool`
+    /// let ty = Type::fn_ptr(vec!["u32"], Type::new("bool"));
+    /// ```
+    pub fn fn_ptr<I, T>(params: I, ret: impl Into<Option<Type>>) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<Type>,
+    {
+        Type::FnPtr {
+            params: params.into_iter().map(Into::into).collect(),
+            ret: ret.into().map(Box::new),
+        }
+    }
+
+    /// Return a new `Fn`, `FnMut` or `FnOnce` trait with parenthesized
+    /// arguments.
+    pub fn fn_trait<I, T>(
+        name: impl Into<String>,
+        params: I,
+        ret: impl Into<Option<Type>>,
+    ) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<Type>,
+    {
+        Type::FnTrait {
+            name: name.into(),
+            params: params.into_iter().map(Into::into).collect(),
+            ret: ret.into().map(Box::new),
+        }
+    }
+
+    /// Return a new trait object type with the given bounds.
+    pub fn dyn_trait<I, T>(bounds: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<Type>,
+    {
+        Type::DynTrait(bounds.into_iter().map(Into::into).collect())
+    }
+
+    /// Return a new `impl Trait` type with the given bounds.
+    pub fn impl_trait<I, T>(bounds: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<Type>,
+    {
+        Type::ImplTrait(bounds.into_iter().map(Into::into).collect())
+    }
+
+    /// Return a new qualified path, e.g. `<T as Trait>::Assoc`.
+    pub fn qpath<T>(
+        qself: T,
+        as_trait: impl Into<Option<Type>>,
+        name: impl Into<String>,
+    ) -> Self
+    where
+        T: Into<Type>,
+    {
+        Type::QPath {
+            qself: Box::new(qself.into()),
+            as_trait: as_trait.into().map(Box::new),
+            name: name.into(),
+        }
+    }
+
     /// Add a generic to the type.
+    ///
+    /// # Panics
+    ///
+    /// Only path types accept generic arguments.
     pub fn generic<T>(&mut self, ty: T) -> &mut Self
     where
         T: Into<Type>,
     {
-        // Make sure that the name doesn't already include generics
-        assert!(
-            !self.name.contains("<"),
-            "type name already includes generics"
-        );
+        match self {
+            Type::Path { name, generics } => {
+                // Make sure that the name doesn't already include generics
+                assert!(
+                    !name.contains("<"),
+                    "type name already includes generics"
+                );
+
+                generics.push(ty.into());
+            }
+            _ => panic!("only path types accept generic arguments"),
+        }
 
-        self.generics.push(ty.into());
         self
     }
 
-    /// Rewrite the `Type` with the provided path
+    /// Rewrite the `Type` with the provided path prepended.
     ///
-    /// TODO: Is this needed?
+    /// # Panics
+    ///
+    /// Only path types can be re-pathed.
     pub fn path(&self, path: impl Into<String>) -> Type {
-        // TODO: This isn't really correct
-        assert!(!self.name.contains("::"));
-
-        let mut name = path.into();
-        name.push_str("::");
-        name.push_str(&self.name);
+        match self {
+            Type::Path { name, generics } => {
+                let mut path = path.into();
+                path.push_str("::");
+                path.push_str(name);
 
-        Type {
-            name,
-            generics: self.generics.clone(),
+                Type::Path {
+                    name: path,
+                    generics: generics.clone(),
+                }
+            }
+            _ => panic!("only path types can be re-pathed"),
         }
     }
 
     /// Formats the struct using the given formatter.
     pub fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
-        write!(fmt, "{}", self.name)?;
-        Type::fmt_slice(&self.generics, fmt)
-    }
+        match self {
+            Type::Path { name, generics } => {
+                fmt.write_type_path(name)?;
+                Type::fmt_slice(generics, fmt)
+            }
+            Type::Lifetime(name) => write!(fmt, "{}", name),
+            Type::Binding { name, ty } => {
+                write!(fmt, "{} = ", name)?;
+                ty.fmt(fmt)
+            }
+            Type::Reference { lifetime, mutable, inner } => {
+                write!(fmt, "&")?;
 
-    fn fmt_slice(generics: &[Type], fmt: &mut Formatter) -> fmt::Result {
-        if !generics.is_empty() {
-            write!(fmt, "<")?;
+                if let Some(lifetime) = lifetime {
+                    write!(fmt, "{} ", lifetime)?;
+                }
+
+                if *mutable {
+                    write!(fmt, "mut ")?;
+                }
+
+                inner.fmt_pointee(fmt)
+            }
+            Type::Ptr { mutable, inner } => {
+                if *mutable {
+                    write!(fmt, "*mut ")?;
+                } else {
+                    write!(fmt, "*const ")?;
+                }
 
-            for (i, ty) in generics.iter().enumerate() {
-                if i != 0 {
-                    write!(fmt, ", ")?
+                inner.fmt_pointee(fmt)
+            }
+            Type::Tuple(tys) => {
+                write!(fmt, "(")?;
+                Type::fmt_list(tys, fmt)?;
+
+                if tys.len() == 1 {
+                    write!(fmt, ",")?;
                 }
-                ty.fmt(fmt)?;
+
+                write!(fmt, ")")
+            }
+            Type::Array { inner, len } => {
+                write!(fmt, "[")?;
+                inner.fmt(fmt)?;
+                write!(fmt, "; {}]", len)
+            }
+            Type::Slice(inner) => {
+                write!(fmt, "[")?;
+                inner.fmt(fmt)?;
+                write!(fmt, "]")
+            }
+            Type::FnPtr { params, ret } => {
+                write!(fmt, "fn")?;
+                Type::fmt_fn_sig(params, ret, fmt)
+            }
+            Type::FnTrait { name, params, ret } => {
+                write!(fmt, "{}", name)?;
+                Type::fmt_fn_sig(params, ret, fmt)
+            }
+            Type::DynTrait(bounds) => {
+                write!(fmt, "dyn ")?;
+                fmt_bound_rhs(bounds, fmt)
+            }
+            Type::ImplTrait(bounds) => {
+                write!(fmt, "impl ")?;
+                fmt_bound_rhs(bounds, fmt)
+            }
+            Type::QPath { qself, as_trait, name } => {
+                write!(fmt, "<")?;
+                qself.fmt(fmt)?;
+
+                if let Some(as_trait) = as_trait {
+                    write!(fmt, " as ")?;
+                    as_trait.fmt(fmt)?;
+                }
+
+                write!(fmt, ">::{}", name)
+            }
+        }
+    }
+
+    /// Formats the type behind a reference or pointer, wrapping trait objects
+    /// with multiple bounds in parentheses.
+    fn fmt_pointee(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            Type::DynTrait(bounds) | Type::ImplTrait(bounds)
+                if bounds.len() > 1 =>
+            {
+                write!(fmt, "(")?;
+                self.fmt(fmt)?;
+                write!(fmt, ")")
             }
+            _ => self.fmt(fmt),
+        }
+    }
+
+    fn fmt_fn_sig(
+        params: &[Type],
+        ret: &Option<Box<Type>>,
+        fmt: &mut Formatter,
+    ) -> fmt::Result {
+        write!(fmt, "(")?;
+        Type::fmt_list(params, fmt)?;
+        write!(fmt, ")")?;
+
+        if let Some(ret) = ret {
+            write!(fmt, " -> ")?;
+            ret.fmt(fmt)?;
+        }
 
+        Ok(())
+    }
+
+    fn fmt_list(tys: &[Type], fmt: &mut Formatter) -> fmt::Result {
+        for (i, ty) in tys.iter().enumerate() {
+            if i != 0 {
+                write!(fmt, ", ")?
+            }
+            ty.fmt(fmt)?;
+        }
+
+        Ok(())
+    }
+
+    fn fmt_slice(generics: &[Type], fmt: &mut Formatter) -> fmt::Result {
+        if !generics.is_empty() {
+            write!(fmt, "<")?;
+            Type::fmt_list(generics, fmt)?;
             write!(fmt, ">")?;
         }
 
@@ -76,24 +473,23 @@ impl Type {
     }
 }
 
+/// Parses the string with `Type::parse`, falling back to a path type named
+/// by the verbatim string if it cannot be parsed.
 impl<'a> From<&'a str> for Type {
     fn from(src: &'a str) -> Self {
-        Type::new(src)
+        Type::parse(src).unwrap_or_else(|_| Type::new(src))
     }
 }
 
 impl From<String> for Type {
     fn from(src: String) -> Self {
-        Type {
-            name: src,
-            generics: vec![],
-        }
+        Type::from(&src[..])
     }
 }
 
 impl<'a> From<&'a String> for Type {
     fn from(src: &'a String) -> Self {
-        Type::new(src)
+        Type::from(&src[..])
     }
 }
 
@@ -0,0 +1,150 @@
+use std::fmt::{self, Write};
+
+use crate::attr::Attr;
+use crate::cfg::Cfg;
+use crate::formatter::{Formatter, Format};
+use crate::generic_param::GenericParam;
+use crate::type_def::TypeDef;
+use crate::visibility::Visibility;
+use crate::where_predicate::WherePredicate;
+
+use crate::r#type::Type;
+
+
+/// Defines a type alias, e.g. `type Result<T> = std::result::Result<T, Error>;`.
+#[derive(Debug, Clone)]
+pub struct TypeAlias {
+    type_def: TypeDef,
+
+    /// Aliased type
+    target: Type,
+}
+
+
+impl TypeAlias {
+    /// Return a type alias with the provided name and aliased type.
+    pub fn new<S, T>(name: S, target: T) -> Self
+    where
+        S: Into<String>,
+        T: Into<Type>,
+    {
+        TypeAlias {
+            type_def: TypeDef::new(name),
+            target: target.into(),
+        }
+    }
+
+    /// Returns a reference to the alias type.
+    pub fn ty(&self) -> &Type {
+        &self.type_def.ty
+    }
+
+    /// Returns the visibility.
+    pub fn visibility(&self) -> &Visibility {
+        self.type_def.visibility()
+    }
+
+    /// Returns a reference to the aliased type.
+    pub fn target(&self) -> &Type {
+        &self.target
+    }
+
+    /// Set the alias visibility.
+    pub fn vis(&mut self, vis: impl Into<Visibility>) -> &mut Self {
+        self.type_def.vis(vis);
+        self
+    }
+
+    /// Add a generic to the alias.
+    pub fn generic(&mut self, param: impl Into<GenericParam>) -> &mut Self {
+        self.type_def.generic(param.into());
+        self
+    }
+
+    /// Add a `where` bound to the alias.
+    pub fn bound<S, T>(&mut self, name: S, ty: T) -> &mut Self
+    where
+        S: Into<Type>,
+        T: Into<Type>,
+    {
+        self.type_def.bound(name, ty);
+        self
+    }
+
+    /// Push a `where` predicate to the alias.
+    pub fn push_predicate(&mut self, pred: WherePredicate) -> &mut Self {
+        self.type_def.push_predicate(pred);
+        self
+    }
+
+    /// Returns the `where` predicate on the given type, creating it if it
+    /// does not exist.
+    pub fn new_predicate<T>(&mut self, bounded: T) -> &mut WherePredicate
+    where
+        T: Into<Type>,
+    {
+        self.type_def.new_predicate(bounded)
+    }
+
+    /// Push an outer attribute.
+    pub fn push_attr(&mut self, attr: Attr) -> &mut Self {
+        self.type_def.push_attr(attr);
+        self
+    }
+
+    /// Create an outer attribute.
+    pub fn new_attr(&mut self, name: impl Into<String>) -> &mut Attr {
+        self.type_def.new_attr(name)
+    }
+
+    /// Push a `#[cfg(pred)]` attribute.
+    pub fn cfg(&mut self, pred: Cfg) -> &mut Self {
+        self.push_attr(Attr::cfg(pred))
+    }
+
+    /// Push a `#[cfg_attr(pred, attr)]` attribute.
+    pub fn cfg_attr(&mut self, pred: Cfg, attr: Attr) -> &mut Self {
+        self.push_attr(Attr::cfg_attr(pred, attr))
+    }
+
+    /// Set the alias documentation.
+    pub fn doc(&mut self, docs: impl Into<String>) -> &mut Self {
+        self.type_def.doc(docs);
+        self
+    }
+
+    /// Specify lint attribute to supress a warning or error.
+    pub fn allow(&mut self, allow: impl Into<String>) -> &mut Self {
+        self.type_def.allow(allow);
+        self
+    }
+
+    /// Add a macro to the alias (e.g. `"#[cfg(unix)]"`)
+    pub fn r#macro(&mut self, r#macro: impl Into<String>) -> &mut Self {
+        self.type_def.r#macro(r#macro);
+        self
+    }
+}
+
+
+impl Format for TypeAlias {
+    /// Formats the type alias using the given formatter.
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        self.type_def.fmt_signature("type", &[], fmt)?;
+        write!(fmt, " = ")?;
+        self.target.fmt(fmt)?;
+
+        // The `where` clause of an alias follows the aliased type
+        for (i, pred) in self.type_def.predicates().iter().enumerate() {
+            if i == 0 {
+                write!(fmt, "\nwhere ")?;
+            } else {
+                write!(fmt, ",\n      ")?;
+            }
+
+            pred.fmt(fmt)?;
+        }
+
+        write!(fmt, ";")
+    }
+}
@@ -1,8 +1,12 @@
 use std::fmt::{self, Write};
 
-use crate::bound::Bound;
+use crate::attr::{Attr, push_derive, set_attr};
+use crate::attr_arg::AttrArg;
 use crate::docs::Docs;
-use crate::formatter::{Formatter, fmt_bounds};
+use crate::formatter::{Formatter, fmt_bounds, fmt_generics};
+use crate::generic_param::{GenericParam, push_generic};
+use crate::visibility::Visibility;
+use crate::where_predicate::{WherePredicate, push_predicate};
 
 use crate::r#type::Type;
 
@@ -11,13 +15,11 @@ use crate::r#type::Type;
 #[derive(Debug, Clone)]
 pub struct TypeDef {
     pub ty: Type,
-    vis: Option<String>,
+    vis: Visibility,
+    generics: Vec<GenericParam>,
     docs: Option<Docs>,
-    derive: Vec<String>,
-    allow: Option<String>,
-    repr: Option<String>,
-    bounds: Vec<Bound>,
-    macros: Vec<String>,
+    attrs: Vec<Attr>,
+    bounds: Vec<WherePredicate>,
 }
 
 
@@ -26,33 +28,63 @@ impl TypeDef {
     pub fn new(name: impl Into<String>) -> Self {
         TypeDef {
             ty: Type::new(name),
-            vis: None,
+            vis: Visibility::Private,
+            generics: vec![],
             docs: None,
-            derive: vec![],
-            allow: None,
-            repr: None,
+            attrs: vec![],
             bounds: vec![],
-            macros: vec![],
         }
     }
 
-    pub fn vis(&mut self, vis: impl Into<String>) {
-        self.vis = Some(vis.into());
+    pub fn vis(&mut self, vis: impl Into<Visibility>) {
+        self.vis = vis.into();
+    }
+
+    pub fn visibility(&self) -> &Visibility {
+        &self.vis
+    }
+
+    pub fn generic(&mut self, param: GenericParam) {
+        let ty = param.to_type();
+        let idx = push_generic(&mut self.generics, param);
+
+        match &mut self.ty {
+            Type::Path { generics, .. } => generics.insert(idx, ty),
+            ty => panic!("type definitions must be named by a path, not {:?}", ty),
+        }
     }
 
     pub fn bound<S, T>(&mut self, name: S, ty: T)
     where
-        S: Into<String>,
+        S: Into<Type>,
         T: Into<Type>,
     {
-        self.bounds.push(Bound {
-            name: name.into(),
-            bound: vec![ty.into()],
-        });
+        self.new_predicate(name).bound(ty);
+    }
+
+    pub fn push_predicate(&mut self, pred: WherePredicate) {
+        push_predicate(&mut self.bounds, pred);
+    }
+
+    pub fn new_predicate(&mut self, bounded: impl Into<Type>) -> &mut WherePredicate {
+        push_predicate(&mut self.bounds, WherePredicate::new(bounded))
+    }
+
+    pub fn predicates(&self) -> &[WherePredicate] {
+        &self.bounds
     }
 
     pub fn r#macro(&mut self, r#macro: impl Into<String>) {
-        self.macros.push(r#macro.into());
+        self.push_attr(Attr::from(r#macro.into()));
+    }
+
+    pub fn push_attr(&mut self, attr: Attr) {
+        self.attrs.push(attr);
+    }
+
+    pub fn new_attr(&mut self, name: impl Into<String>) -> &mut Attr {
+        self.push_attr(Attr::new(name));
+        self.attrs.last_mut().unwrap()
     }
 
     pub fn doc(&mut self, docs: impl Into<String>) {
@@ -60,15 +92,15 @@ impl TypeDef {
     }
 
     pub fn derive(&mut self, name: impl Into<String>) {
-        self.derive.push(name.into());
+        push_derive(&mut self.attrs, &name.into());
     }
 
     pub fn allow(&mut self, allow: impl Into<String>) {
-        self.allow = Some(allow.into());
+        set_attr(&mut self.attrs, "allow", AttrArg::delimited(allow));
     }
 
     pub fn repr(&mut self, repr: impl Into<String>) {
-        self.repr = Some(repr.into());
+        set_attr(&mut self.attrs, "repr", AttrArg::delimited(repr));
     }
 
     pub fn fmt_head(
@@ -76,22 +108,38 @@ impl TypeDef {
         keyword: impl AsRef<str>,
         parents: &[Type],
         fmt: &mut Formatter
+    ) -> fmt::Result {
+        self.fmt_signature(keyword, parents, fmt)?;
+        fmt_bounds(&self.bounds, fmt)
+    }
+
+    /// Formats the head without the `where` clause.
+    pub fn fmt_signature(
+        &self,
+        keyword: impl AsRef<str>,
+        parents: &[Type],
+        fmt: &mut Formatter
     ) -> fmt::Result {
         if let Some(docs) = &self.docs {
             docs.fmt(fmt, false)?;
         }
 
-        self.fmt_allow(fmt)?;
-        self.fmt_derive(fmt)?;
-        self.fmt_repr(fmt)?;
-        self.fmt_macros(fmt)?;
+        for attr in &self.attrs {
+            attr.fmt(fmt, false)?;
+        }
 
-        if let Some(ref vis) = self.vis {
-            write!(fmt, "{} ", vis)?;
+        if !self.vis.is_private() {
+            write!(fmt, "{} ", self.vis)?;
         }
 
         write!(fmt, "{} ", keyword.as_ref())?;
-        self.ty.fmt(fmt)?;
+
+        match &self.ty {
+            Type::Path { name, .. } => write!(fmt, "{}", name)?,
+            ty => panic!("type definitions must be named by a path, not {:?}", ty),
+        }
+
+        fmt_generics(&self.generics, fmt)?;
 
         if !parents.is_empty() {
             for (i, ty) in parents.iter().enumerate() {
@@ -105,48 +153,6 @@ impl TypeDef {
             }
         }
 
-        fmt_bounds(&self.bounds, fmt)?;
-
-        Ok(())
-    }
-
-    fn fmt_allow(&self, fmt: &mut Formatter) -> fmt::Result {
-        if let Some(ref allow) = self.allow {
-            writeln!(fmt, "#[allow({})]", allow)?;
-        }
-
-        Ok(())
-    }
-
-    fn fmt_repr(&self, fmt: &mut Formatter) -> fmt::Result {
-        if let Some(ref repr) = self.repr {
-            writeln!(fmt, "#[repr({})]", repr)?;
-        }
-
-        Ok(())
-    }
-
-    fn fmt_derive(&self, fmt: &mut Formatter) -> fmt::Result {
-        if !self.derive.is_empty() {
-            write!(fmt, "#[derive(")?;
-
-            for (i, name) in self.derive.iter().enumerate() {
-                if i != 0 {
-                    write!(fmt, ", ")?
-                }
-                write!(fmt, "{}", name)?;
-            }
-
-            writeln!(fmt, ")]")?;
-        }
-
-        Ok(())
-    }
-
-    fn fmt_macros(&self, fmt: &mut Formatter) -> fmt::Result {
-        for m in self.macros.iter() {
-            writeln!(fmt, "{}", m)?;
-        }
         Ok(())
     }
 }
@@ -0,0 +1,170 @@
+use std::fmt;
+
+use crate::attr::Attr;
+use crate::cfg::Cfg;
+use crate::field::Field;
+use crate::fields::Fields;
+use crate::formatter::{Formatter, Format};
+use crate::generic_param::GenericParam;
+use crate::type_def::TypeDef;
+use crate::visibility::Visibility;
+use crate::where_predicate::WherePredicate;
+
+use crate::r#type::Type;
+
+
+/// Defines a union, e.g. for FFI with `#[repr(C)]`.
+#[derive(Debug, Clone)]
+pub struct Union {
+    type_def: TypeDef,
+
+    /// Union fields
+    fields: Fields,
+}
+
+
+impl Union {
+    /// Return a union definition with the provided name
+    pub fn new(name: impl Into<String>) -> Self {
+        Union {
+            type_def: TypeDef::new(name),
+            fields: Fields::Empty,
+        }
+    }
+
+    /// Returns a reference to the type
+    pub fn ty(&self) -> &Type {
+        &self.type_def.ty
+    }
+
+    /// Returns the visibility.
+    pub fn visibility(&self) -> &Visibility {
+        self.type_def.visibility()
+    }
+
+    /// Set the union visibility.
+    pub fn vis(&mut self, vis: impl Into<Visibility>) -> &mut Self {
+        self.type_def.vis(vis);
+        self
+    }
+
+    /// Add a generic to the union.
+    pub fn generic(&mut self, param: impl Into<GenericParam>) -> &mut Self {
+        self.type_def.generic(param.into());
+        self
+    }
+
+    /// Add a `where` bound to the union.
+    pub fn bound<S, T>(&mut self, name: S, ty: T) -> &mut Self
+    where
+        S: Into<Type>,
+        T: Into<Type>,
+    {
+        self.type_def.bound(name, ty);
+        self
+    }
+
+    /// Push a `where` predicate to the union.
+    ///
+    /// Bounds on a type that is already constrained are merged into the
+    /// existing predicate.
+    pub fn push_predicate(&mut self, pred: WherePredicate) -> &mut Self {
+        self.type_def.push_predicate(pred);
+        self
+    }
+
+    /// Returns the `where` predicate on the given type, creating it if it
+    /// does not exist.
+    pub fn new_predicate<T>(&mut self, bounded: T) -> &mut WherePredicate
+    where
+        T: Into<Type>,
+    {
+        self.type_def.new_predicate(bounded)
+    }
+
+    /// Push an outer attribute.
+    pub fn push_attr(&mut self, attr: Attr) -> &mut Self {
+        self.type_def.push_attr(attr);
+        self
+    }
+
+    /// Create an outer attribute.
+    pub fn new_attr(&mut self, name: impl Into<String>) -> &mut Attr {
+        self.type_def.new_attr(name)
+    }
+
+    /// Push a `#[cfg(pred)]` attribute.
+    pub fn cfg(&mut self, pred: Cfg) -> &mut Self {
+        self.push_attr(Attr::cfg(pred))
+    }
+
+    /// Push a `#[cfg_attr(pred, attr)]` attribute.
+    pub fn cfg_attr(&mut self, pred: Cfg, attr: Attr) -> &mut Self {
+        self.push_attr(Attr::cfg_attr(pred, attr))
+    }
+
+    /// Set the union documentation.
+    pub fn doc(&mut self, docs: impl Into<String>) -> &mut Self {
+        self.type_def.doc(docs);
+        self
+    }
+
+    /// Add a new type that the union should derive.
+    pub fn derive(&mut self, name: impl Into<String>) -> &mut Self {
+        self.type_def.derive(name);
+        self
+    }
+
+    /// Specify lint attribute to supress a warning or error.
+    pub fn allow(&mut self, allow: impl Into<String>) -> &mut Self {
+        self.type_def.allow(allow);
+        self
+    }
+
+    /// Specify representation.
+    pub fn repr(&mut self, repr: impl Into<String>) -> &mut Self {
+        self.type_def.repr(repr);
+        self
+    }
+
+    /// Push a named field to the union.
+    pub fn push_field(&mut self, field: Field) -> &mut Self {
+        self.fields.push_named(field);
+        self
+    }
+
+    /// Add a named field to the union.
+    pub fn field<S, T>(&mut self, name: S, ty: T) -> &mut Self
+    where
+        S: Into<String>,
+        T: Into<Type>,
+    {
+        self.fields.named(name, ty);
+        self
+    }
+
+    /// Add a public named field to the union.
+    pub fn field_pub<S, T>(&mut self, name: S, ty: T) -> &mut Self
+    where
+        S: Into<String>,
+        T: Into<Type>,
+    {
+        let mut field = Field::new(name, ty);
+        field.vis("pub");
+        self.fields.push_named(field);
+        self
+    }
+}
+
+
+impl Format for Union {
+    /// Formats the union using the given formatter.
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        self.type_def.fmt_head("union", &[], fmt)?;
+
+        match self.fields {
+            Fields::Empty => fmt.block(|_| Ok(())),
+            _ => self.fields.fmt(fmt),
+        }
+    }
+}
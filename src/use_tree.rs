@@ -0,0 +1,325 @@
+use std::fmt::{self, Write};
+
+use crate::formatter::Formatter;
+use crate::parse::{ParseError, Parser};
+
+
+/// Defines the tree of a `use` declaration, e.g. the
+/// `foo::{self, bar::{X, Y as Z}, baz::*}` in
+/// `use foo::{self, bar::{X, Y as Z}, baz::*};`.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum UseTree {
+    /// A path segment followed by a subtree, e.g. `foo::Bar`.
+    ///
+    /// An empty segment stands for a leading `::`.
+    Path(String, Box<UseTree>),
+
+    /// A name, e.g. `Bar` or `self`.
+    Name(String),
+
+    /// A renamed name, e.g. `Bar as Baz` or `Bar as _`.
+    Rename(String, String),
+
+    /// A glob, `*`.
+    Glob,
+
+    /// A group of subtrees, e.g. `{Bar, baz::*}`.
+    Group(Vec<UseTree>),
+}
+
+
+impl UseTree {
+    /// Return a new tree importing the given name.
+    pub fn name(name: impl Into<String>) -> Self {
+        UseTree::Name(name.into())
+    }
+
+    /// Return a new tree importing `name` as `alias`.
+    pub fn rename<S, T>(name: S, alias: T) -> Self
+    where
+        S: Into<String>,
+        T: Into<String>,
+    {
+        UseTree::Rename(name.into(), alias.into())
+    }
+
+    /// Return a new glob, `*`.
+    pub fn glob() -> Self {
+        UseTree::Glob
+    }
+
+    /// Return a new group of subtrees.
+    pub fn group<I, T>(trees: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<UseTree>,
+    {
+        UseTree::Group(trees.into_iter().map(Into::into).collect())
+    }
+
+    /// Return the tree prefixed by the given path, e.g. `std::fmt`.
+    pub fn path(prefix: impl AsRef<str>, tree: impl Into<UseTree>) -> Self {
+        prefix
+            .as_ref()
+            .rsplit("::")
+            .fold(tree.into(), |tree, seg| {
+                UseTree::Path(seg.to_string(), Box::new(tree))
+            })
+    }
+
+    /// Parse a use tree, e.g. `foo::{self, Bar as Baz}`.
+    ///
+    /// ```
+    /// use codegen::UseTree;
+    ///
+    /// let tree = UseTree::parse("std::{fmt, io::*}").unwrap();
+    ///
+    /// assert_eq!(tree, UseTree::path("std", UseTree::group(vec![
+    ///     UseTree::name("fmt"),
+    ///     UseTree::path("io", UseTree::glob()),
+    /// ])));
+    /// ```
+    pub fn parse(src: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser::new(src);
+        let tree = parse_tree(&mut parser)?;
+        parser.finish()?;
+        Ok(tree)
+    }
+
+    /// Split the tree into its leaves, each a chain of path segments ending
+    /// in a name, rename or glob.
+    pub(crate) fn leaves(&self) -> Vec<(Vec<String>, UseTree)> {
+        let mut leaves = vec![];
+        self.collect_leaves(&mut vec![], &mut leaves);
+        leaves
+    }
+
+    fn collect_leaves(
+        &self,
+        prefix: &mut Vec<String>,
+        leaves: &mut Vec<(Vec<String>, UseTree)>,
+    ) {
+        match self {
+            UseTree::Path(seg, tree) => {
+                prefix.push(seg.clone());
+                tree.collect_leaves(prefix, leaves);
+                prefix.pop();
+            }
+            UseTree::Group(trees) => {
+                for tree in trees {
+                    tree.collect_leaves(prefix, leaves);
+                }
+            }
+            leaf => leaves.push((prefix.clone(), leaf.clone())),
+        }
+    }
+
+    /// Formats the tree using the given formatter.
+    pub fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            UseTree::Path(seg, tree) => {
+                write!(fmt, "{}::", seg)?;
+                tree.fmt(fmt)
+            }
+            UseTree::Name(name) => write!(fmt, "{}", name),
+            UseTree::Rename(name, alias) => write!(fmt, "{} as {}", name, alias),
+            UseTree::Glob => write!(fmt, "*"),
+            UseTree::Group(trees) => {
+                write!(fmt, "{{")?;
+
+                for (i, tree) in trees.iter().enumerate() {
+                    if i != 0 {
+                        write!(fmt, ", ")?;
+                    }
+                    tree.fmt(fmt)?;
+                }
+
+                write!(fmt, "}}")
+            }
+        }
+    }
+}
+
+/// Parses the string, falling back to a verbatim name if it is not a valid
+/// use tree.
+impl<'a> From<&'a str> for UseTree {
+    fn from(src: &'a str) -> Self {
+        UseTree::parse(src).unwrap_or_else(|_| UseTree::Name(src.to_string()))
+    }
+}
+
+impl From<String> for UseTree {
+    fn from(src: String) -> Self {
+        UseTree::from(&src[..])
+    }
+}
+
+impl<'a> From<&'a String> for UseTree {
+    fn from(src: &'a String) -> Self {
+        UseTree::from(&src[..])
+    }
+}
+
+impl<'a> From<&'a UseTree> for UseTree {
+    fn from(src: &'a UseTree) -> Self {
+        src.clone()
+    }
+}
+
+
+/// Use trees sharing a common prefix, merged into nested groups.
+#[derive(Debug, Default)]
+pub(crate) struct UseGroup {
+    entries: Vec<Entry>,
+}
+
+
+#[derive(Debug)]
+enum Entry {
+    Leaf(UseTree),
+    Child(String, UseGroup),
+}
+
+
+impl Entry {
+    fn sort_key(&self) -> (usize, &str) {
+        match self {
+            Entry::Leaf(UseTree::Name(name)) | Entry::Leaf(UseTree::Rename(name, _)) => {
+                (if name == "self" { 0 } else { 1 }, name)
+            }
+            Entry::Leaf(_) => (2, ""),
+            Entry::Child(seg, _) => (1, seg),
+        }
+    }
+}
+
+
+impl UseGroup {
+    /// Insert a leaf, given as path segments and a name, rename or glob.
+    pub(crate) fn insert(&mut self, segs: &[String], leaf: &UseTree) {
+        match segs.split_first() {
+            Some((seg, rest)) => {
+                let pos = self.entries.iter().position(|entry| match entry {
+                    Entry::Child(name, _) => name == seg,
+                    Entry::Leaf(_) => false,
+                });
+
+                let idx = pos.unwrap_or_else(|| {
+                    self.entries.push(Entry::Child(seg.clone(), UseGroup::default()));
+                    self.entries.len() - 1
+                });
+
+                match &mut self.entries[idx] {
+                    Entry::Child(_, group) => group.insert(rest, leaf),
+                    Entry::Leaf(_) => unreachable!(),
+                }
+            }
+            None => {
+                let exists = self.entries.iter().any(|entry| match entry {
+                    Entry::Leaf(existing) => existing == leaf,
+                    Entry::Child(..) => false,
+                });
+
+                if !exists {
+                    self.entries.push(Entry::Leaf(leaf.clone()));
+                }
+            }
+        }
+    }
+
+    /// Returns true if the group has no leaves.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Sort the group as rustfmt does: `self` first, then names and
+    /// paths alphabetically, then globs.
+    pub(crate) fn sort(&mut self) {
+        for entry in &mut self.entries {
+            if let Entry::Child(_, group) = entry {
+                group.sort();
+            }
+        }
+
+        self.entries.sort_by(|a, b| a.sort_key().cmp(&b.sort_key()));
+    }
+
+    /// Formats the group as the tree following `prefix`, e.g. `prefix`,
+    /// `prefix::Foo` or `prefix::{self, Foo}`.
+    pub(crate) fn fmt_with_prefix(&self, prefix: &str, fmt: &mut Formatter) -> fmt::Result {
+        if prefix.is_empty() || prefix == "::" {
+            // A glob without a path
+            write!(fmt, "{}", prefix)?;
+            return self.fmt(fmt);
+        }
+
+        match &self.entries[..] {
+            [Entry::Leaf(UseTree::Name(name))] if name == "self" => {
+                write!(fmt, "{}", prefix)
+            }
+            [Entry::Leaf(UseTree::Rename(name, alias))] if name == "self" => {
+                write!(fmt, "{} as {}", prefix, alias)
+            }
+            _ => {
+                write!(fmt, "{}::", prefix)?;
+                self.fmt(fmt)
+            }
+        }
+    }
+
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        if self.entries.len() != 1 {
+            write!(fmt, "{{")?;
+        }
+
+        for (i, entry) in self.entries.iter().enumerate() {
+            if i != 0 {
+                write!(fmt, ", ")?;
+            }
+
+            match entry {
+                Entry::Leaf(leaf) => leaf.fmt(fmt)?,
+                Entry::Child(seg, group) => group.fmt_with_prefix(seg, fmt)?,
+            }
+        }
+
+        if self.entries.len() != 1 {
+            write!(fmt, "}}")?;
+        }
+
+        Ok(())
+    }
+}
+
+
+fn parse_tree(parser: &mut Parser) -> Result<UseTree, ParseError> {
+    if parser.eat("*") {
+        return Ok(UseTree::Glob);
+    }
+
+    if parser.eat("{") {
+        let (trees, _) = parser.parse_list("}", parse_tree)?;
+        return Ok(UseTree::Group(trees));
+    }
+
+    if parser.eat("::") {
+        return Ok(UseTree::Path(String::new(), Box::new(parse_tree(parser)?)));
+    }
+
+    let name = match parser.ident() {
+        Some(name) => name,
+        None => return parser.error("expected identifier"),
+    };
+
+    if parser.eat("::") {
+        Ok(UseTree::Path(name, Box::new(parse_tree(parser)?)))
+    } else if parser.eat_keyword("as") {
+        match parser.ident() {
+            Some(alias) => Ok(UseTree::Rename(name, alias)),
+            None => parser.error("expected identifier"),
+        }
+    } else {
+        Ok(UseTree::Name(name))
+    }
+}
@@ -1,6 +1,10 @@
 use std::fmt::{self, Write};
 use crate::r#type::Type;
+use crate::attr::Attr;
+use crate::cfg::Cfg;
+use crate::docs::Docs;
 use crate::formatter::{Formatter, Format};
+use crate::visibility::Visibility;
 
 /// Static variable.
 #[derive(Debug, Clone)]
@@ -9,10 +13,16 @@ pub struct VarDef {
     /// variable name
     pub name: String,
     ty: Type,
-    value: String,
+    value: Option<String>,
 
     /// visibility
-    pub vis: Option<String>,
+    pub vis: Visibility,
+
+    /// documentation
+    docs: Option<Docs>,
+
+    /// attributes, e.g. `#[no_mangle]`
+    attrs: Vec<Attr>,
 }
 
 
@@ -21,6 +31,8 @@ pub struct VarDef {
 pub enum VarDefKind {
     /// Static variable definition.
     Static,
+    /// Mutable static variable definition, i.e. `static mut`.
+    StaticMut,
     /// Constant definition.
     Const,
 }
@@ -37,8 +49,10 @@ impl VarDef {
             kind,
             name: name.into(),
             ty: ty.into(),
-            value: "".to_owned(),
-            vis: None,
+            value: None,
+            vis: Visibility::Private,
+            docs: None,
+            attrs: vec![],
         }
     }
 
@@ -51,6 +65,15 @@ impl VarDef {
         Self::new(VarDefKind::Static, name, ty)
     }
 
+    /// Return a new mutable static variable definition.
+    pub fn new_static_mut<S, T>(name: S, ty: T) -> Self
+    where
+        S: Into<String>,
+        T: Into<Type>,
+    {
+        Self::new(VarDefKind::StaticMut, name, ty)
+    }
+
     /// Return a new constant definition.
     pub fn new_const<S, T>(name: S, ty: T) -> Self
     where
@@ -60,28 +83,131 @@ impl VarDef {
         Self::new(VarDefKind::Const, name, ty)
     }
 
+    /// Return a new unnamed constant, i.e. `const _: () = value;`, e.g. for
+    /// compile-time assertions.
+    pub fn new_unnamed_const(value: impl Into<String>) -> Self {
+        let mut var = Self::new_const("_", Type::unit());
+        var.value(value);
+        var
+    }
+
+    /// Returns the kind of the variable.
+    pub fn kind(&self) -> VarDefKind {
+        self.kind
+    }
+
     /// Set the visibility.
-    pub fn vis(&mut self, vis: impl Into<String>) -> &mut Self {
-        self.vis = Some(vis.into());
+    pub fn vis(&mut self, vis: impl Into<Visibility>) -> &mut Self {
+        self.vis = vis.into();
         self
     }
 
     /// Set the value.
+    ///
+    /// A value is required, except for associated constants of traits and
+    /// foreign statics, which must not have one.
     pub fn value(&mut self, value: impl Into<String>) -> &mut Self {
-        self.value = value.into();
+        self.value = Some(value.into());
         self
     }
+
+    /// Set the documentation.
+    pub fn doc(&mut self, docs: impl Into<String>) -> &mut Self {
+        self.docs = Some(Docs::new(docs));
+        self
+    }
+
+    /// Push an attribute, e.g. `#[no_mangle]`.
+    pub fn push_attr(&mut self, attr: Attr) -> &mut Self {
+        self.attrs.push(attr);
+        self
+    }
+
+    /// Create an attribute.
+    pub fn new_attr(&mut self, name: impl Into<String>) -> &mut Attr {
+        self.push_attr(Attr::new(name));
+
+        self.attrs.last_mut().unwrap()
+    }
+
+    /// Push a `#[cfg(pred)]` attribute.
+    pub fn cfg(&mut self, pred: Cfg) -> &mut Self {
+        self.push_attr(Attr::cfg(pred))
+    }
+
+    /// Push a `#[cfg_attr(pred, attr)]` attribute.
+    pub fn cfg_attr(&mut self, pred: Cfg, attr: Attr) -> &mut Self {
+        self.push_attr(Attr::cfg_attr(pred, attr))
+    }
+
+    /// Formats the variable as a declaration in an `extern` block.
+    pub(crate) fn fmt_foreign(&self, fmt: &mut Formatter) -> fmt::Result {
+        assert!(
+            self.kind != VarDefKind::Const,
+            "extern blocks can only declare statics"
+        );
+        assert!(self.value.is_none(), "foreign statics cannot have a value");
+
+        self.fmt_decl(fmt)?;
+        writeln!(fmt)
+    }
+
+    /// Formats the variable as an associated constant of a trait or impl
+    /// block.
+    pub(crate) fn fmt_assoc(&self, is_trait: bool, fmt: &mut Formatter) -> fmt::Result {
+        assert!(
+            self.kind == VarDefKind::Const,
+            "traits and impls can only have associated constants"
+        );
+
+        if is_trait {
+            assert!(
+                self.vis.is_private(),
+                "trait consts do not have visibility modifiers"
+            );
+        } else {
+            assert!(self.value.is_some(), "impl consts must have a value");
+        }
+
+        self.fmt_decl(fmt)?;
+        writeln!(fmt)
+    }
+
+    fn fmt_decl(&self, fmt: &mut Formatter) -> fmt::Result {
+        if let Some(docs) = &self.docs {
+            docs.fmt(fmt, false)?;
+        }
+
+        for attr in &self.attrs {
+            attr.fmt(fmt, false)?;
+        }
+
+        if !self.vis.is_private() {
+            write!(fmt, "{} ", self.vis)?;
+        }
+
+        match self.kind {
+            VarDefKind::Static => write!(fmt, "static")?,
+            VarDefKind::StaticMut => write!(fmt, "static mut")?,
+            VarDefKind::Const => write!(fmt, "const")?,
+        }
+
+        write!(fmt, " {}: ", self.name)?;
+        self.ty.fmt(fmt)?;
+
+        if let Some(value) = &self.value {
+            write!(fmt, " = {}", value)?;
+        }
+
+        write!(fmt, ";")
+    }
 }
 
 
 impl Format for VarDef {
-  fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
-      match self.kind {
-          VarDefKind::Static => write!(fmt, "static")?,
-          VarDefKind::Const => write!(fmt, "const")?,
-      }
-      write!(fmt, " {}: ", self.name)?;
-      self.ty.fmt(fmt)?;
-      write!(fmt, " = {};", self.value)
-  }
-}
\ No newline at end of file
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        assert!(self.value.is_some(), "constants and statics must have a value");
+
+        self.fmt_decl(fmt)
+    }
+}
@@ -1,5 +1,7 @@
 use std::fmt::{self, Write};
 
+use crate::attr::Attr;
+use crate::cfg::Cfg;
 use crate::fields::Fields;
 use crate::formatter::{Formatter, Format};
 use crate::r#enum::EnumVariant;
@@ -12,6 +14,7 @@ use crate::r#type::Type;
 pub struct Variant {
     name: String,
     fields: Fields,
+    attrs: Vec<Attr>,
 }
 
 
@@ -21,6 +24,7 @@ impl EnumVariant for Variant {
         Variant {
             name: name.into(),
             fields: Fields::Empty,
+            attrs: vec![],
         }
     }
 }
@@ -42,11 +46,38 @@ impl Variant {
         self.fields.tuple(ty);
         self
     }
+
+    /// Push an attribute to the variant.
+    pub fn push_attr(&mut self, attr: Attr) -> &mut Self {
+        self.attrs.push(attr);
+        self
+    }
+
+    /// Create an attribute on the variant.
+    pub fn new_attr(&mut self, name: impl Into<String>) -> &mut Attr {
+        self.push_attr(Attr::new(name));
+
+        self.attrs.last_mut().unwrap()
+    }
+
+    /// Push a `#[cfg(pred)]` attribute.
+    pub fn cfg(&mut self, pred: Cfg) -> &mut Self {
+        self.push_attr(Attr::cfg(pred))
+    }
+
+    /// Push a `#[cfg_attr(pred, attr)]` attribute.
+    pub fn cfg_attr(&mut self, pred: Cfg, attr: Attr) -> &mut Self {
+        self.push_attr(Attr::cfg_attr(pred, attr))
+    }
 }
 
 impl Format for Variant {
     /// Formats the variant using the given formatter.
     fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        for attr in &self.attrs {
+            attr.fmt(fmt, false)?;
+        }
+
         write!(fmt, "{}", self.name)?;
         self.fields.fmt(fmt)?;
         writeln!(fmt, ",")
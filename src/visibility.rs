@@ -0,0 +1,143 @@
+use std::fmt;
+
+use crate::parse::{ParseError, Parser};
+
+
+/// Defines the visibility of an item, field or import.
+///
+/// Strings are parsed for compatibility, e.g. `"pub(crate)"`; converting
+/// an invalid string panics. Use `Verbatim` to write any other visibility.
+///
+/// ```
+/// use codegen::Visibility;
+///
+/// assert_eq!(Visibility::from("pub(crate)"), Visibility::Crate);
+/// assert_eq!(Visibility::from("pub(in crate::a)").to_string(), "pub(in crate::a)");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Visibility {
+    /// No visibility modifier.
+    #[default]
+    Private,
+
+    /// `pub`
+    Pub,
+
+    /// `pub(crate)`
+    Crate,
+
+    /// `pub(super)`
+    Super,
+
+    /// `pub(in path)`, e.g. `pub(in crate::model)`. `pub(self)` is
+    /// represented as `In("self")`.
+    In(String),
+
+    /// Any other visibility, written verbatim and never parsed, e.g. a
+    /// macro fragment such as `$vis`.
+    Verbatim(String),
+}
+
+
+impl Visibility {
+    /// Returns true if the visibility is `Private`.
+    pub fn is_private(&self) -> bool {
+        *self == Visibility::Private
+    }
+
+    /// Returns true if the visibility is `pub`.
+    pub fn is_pub(&self) -> bool {
+        *self == Visibility::Pub
+    }
+
+    /// Parses a visibility, e.g. `pub` or `pub(in crate::model)`. An empty
+    /// string is `Private`.
+    pub fn parse(src: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser::new(src);
+
+        let vis = if parser.peek().is_none() {
+            Visibility::Private
+        } else if !parser.eat_keyword("pub") {
+            return parser.error("expected `pub`");
+        } else if !parser.eat("(") {
+            Visibility::Pub
+        } else {
+            let vis = if parser.eat_keyword("crate") {
+                Visibility::Crate
+            } else if parser.eat_keyword("super") {
+                Visibility::Super
+            } else if parser.eat_keyword("self") {
+                Visibility::In("self".to_string())
+            } else if parser.eat_keyword("in") {
+                let mut path = String::new();
+
+                loop {
+                    match parser.ident() {
+                        Some(seg) => path.push_str(&seg),
+                        None => return parser.error("expected identifier"),
+                    }
+
+                    if !parser.eat("::") {
+                        break;
+                    }
+
+                    path.push_str("::");
+                }
+
+                Visibility::In(path)
+            } else {
+                return parser.error("expected `crate`, `super`, `self` or `in`");
+            };
+
+            parser.expect(")")?;
+            vis
+        };
+
+        parser.finish()?;
+        Ok(vis)
+    }
+}
+
+
+impl fmt::Display for Visibility {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Visibility::Private => Ok(()),
+            Visibility::Pub => write!(fmt, "pub"),
+            Visibility::Crate => write!(fmt, "pub(crate)"),
+            Visibility::Super => write!(fmt, "pub(super)"),
+            Visibility::In(path) if path == "self" => write!(fmt, "pub(self)"),
+            Visibility::In(path) => write!(fmt, "pub(in {})", path),
+            Visibility::Verbatim(vis) => write!(fmt, "{}", vis),
+        }
+    }
+}
+
+
+/// Parses the string, panicking if it is not a valid visibility.
+impl<'a> From<&'a str> for Visibility {
+    fn from(src: &'a str) -> Self {
+        match Visibility::parse(src) {
+            Ok(vis) => vis,
+            Err(err) => panic!("invalid visibility `{}`: {}", src, err),
+        }
+    }
+}
+
+impl From<String> for Visibility {
+    fn from(src: String) -> Self {
+        Visibility::from(&src[..])
+    }
+}
+
+impl<'a> From<&'a String> for Visibility {
+    fn from(src: &'a String) -> Self {
+        Visibility::from(&src[..])
+    }
+}
+
+impl<'a> From<&'a Visibility> for Visibility {
+    fn from(src: &'a Visibility) -> Self {
+        src.clone()
+    }
+}
@@ -0,0 +1,125 @@
+use std::fmt::{self, Write};
+
+use crate::formatter::{Formatter, fmt_bound_rhs};
+
+use crate::r#type::Type;
+
+
+/// Defines a predicate of a `where` clause, e.g. `T: Clone + Send`,
+/// `'a: 'b` or `for<'de> T: Deserialize<'de>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WherePredicate {
+    /// Higher-ranked lifetimes, e.g. the `'de` in `for<'de>`
+    lifetimes: Vec<String>,
+
+    /// The bounded type or lifetime
+    bounded: Type,
+
+    /// Bounds on the bounded type
+    bounds: Vec<Type>,
+}
+
+
+impl WherePredicate {
+    /// Return a new predicate on the given type or lifetime.
+    ///
+    /// ```
+    /// use codegen::WherePredicate;
+    ///
+    /// // `'a: 'b`
+    /// let mut pred = WherePredicate::new("'a");
+    /// pred.bound("'b");
+    /// ```
+    pub fn new<T>(bounded: T) -> Self
+    where
+        T: Into<Type>,
+    {
+        WherePredicate {
+            lifetimes: vec![],
+            bounded: bounded.into(),
+            bounds: vec![],
+        }
+    }
+
+    /// Returns the bounded type.
+    pub fn bounded(&self) -> &Type {
+        &self.bounded
+    }
+
+    /// Returns the bounds.
+    pub fn bounds(&self) -> &[Type] {
+        &self.bounds
+    }
+
+    /// Add a higher-ranked lifetime, rendered as `for<'a>`. The leading `'`
+    /// is optional.
+    pub fn for_lifetime(&mut self, name: impl Into<String>) -> &mut Self {
+        let lifetime = match Type::lifetime(name) {
+            Type::Lifetime(lifetime) => lifetime,
+            _ => unreachable!(),
+        };
+
+        if !self.lifetimes.contains(&lifetime) {
+            self.lifetimes.push(lifetime);
+        }
+
+        self
+    }
+
+    /// Add a bound. Bounds that are already present are ignored.
+    pub fn bound<T>(&mut self, ty: T) -> &mut Self
+    where
+        T: Into<Type>,
+    {
+        let ty = ty.into();
+
+        if !self.bounds.contains(&ty) {
+            self.bounds.push(ty);
+        }
+
+        self
+    }
+
+    /// Returns true if `other` constrains the same type under the same
+    /// higher-ranked lifetimes.
+    fn same_target(&self, other: &WherePredicate) -> bool {
+        self.bounded == other.bounded && self.lifetimes == other.lifetimes
+    }
+
+    /// Formats the predicate using the given formatter.
+    pub fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        if !self.lifetimes.is_empty() {
+            write!(fmt, "for<{}> ", self.lifetimes.join(", "))?;
+        }
+
+        self.bounded.fmt(fmt)?;
+        write!(fmt, ": ")?;
+        fmt_bound_rhs(&self.bounds, fmt)
+    }
+}
+
+
+/// Push a predicate, merging its bounds into an existing predicate on the
+/// same type.
+///
+/// Returns a mutable reference to the resulting predicate.
+pub(crate) fn push_predicate(
+    preds: &mut Vec<WherePredicate>,
+    pred: WherePredicate,
+) -> &mut WherePredicate {
+    match preds.iter().position(|p| p.same_target(&pred)) {
+        Some(idx) => {
+            let existing = &mut preds[idx];
+
+            for ty in pred.bounds {
+                existing.bound(ty);
+            }
+
+            existing
+        }
+        None => {
+            preds.push(pred);
+            preds.last_mut().unwrap()
+        }
+    }
+}
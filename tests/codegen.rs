@@ -871,4 +871,1776 @@ fn foo(mut bar: String, baz: bool) -> usize {
 }"#;
 
     assert_eq!(scope.to_string(), &expect[1..]);
-}
\ No newline at end of file
+}
+
+#[test]
+fn struct_with_structured_types() {
+    let mut scope = Scope::new();
+
+    let mut boxed = Type::new("Box");
+    boxed.generic(Type::dyn_trait(vec![
+        Type::fn_trait("Fn", Vec::<Type>::new(), None),
+        Type::new("Send"),
+    ]));
+
+    scope.new_struct("Foo")
+        .generic("'a")
+        .field("buf", Type::reference(Some("a"), true, Type::array("u8", 4)))
+        .field("pair", Type::tuple(vec!["A", "B"]))
+        .field("one", Type::tuple(vec!["A"]))
+        .field("unit", Type::unit())
+        .field("bytes", Type::reference(None, false, Type::slice("u8")))
+        .field("ptr", Type::ptr(false, "c_void"))
+        .field("pred", Type::fn_ptr(vec!["u32"], Type::new("bool")))
+        .field("callback", boxed)
+        .field("obj", Type::reference(None, false, Type::dyn_trait(vec![
+            Type::new("Any"),
+            Type::lifetime("static"),
+        ])))
+        .field("assoc", Type::qpath("T", Type::new("Iterator"), "Item"));
+
+    let expect = r#"
+struct Foo<'a> {
+    buf: &'a mut [u8; 4],
+    pair: (A, B),
+    one: (A,),
+    unit: (),
+    bytes: &[u8],
+    ptr: *const c_void,
+    pred: fn(u32) -> bool,
+    callback: Box<dyn Fn() + Send>,
+    obj: &(dyn Any + 'static),
+    assoc: <T as Iterator>::Item,
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn fn_with_impl_trait() {
+    let mut scope = Scope::new();
+
+    let mut iter = Type::new("Iterator");
+    iter.generic(Type::binding("Item", "u32"));
+
+    scope.new_fn("numbers")
+        .ret(Type::impl_trait(vec![iter]))
+        .line("0..10");
+
+    let expect = r#"
+fn numbers() -> impl Iterator<Item = u32> {
+    0..10
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn parse_type() {
+    let ty = Type::parse("Vec<u8>").unwrap();
+    assert_eq!(ty, Type::new("Vec").generic("u8").clone());
+
+    let ty = Type::parse("&'a mut [u8; 4]").unwrap();
+    assert_eq!(ty, Type::reference(Some("'a"), true, Type::array("u8", 4)));
+
+    assert_eq!(
+        Type::parse("(A, B)").unwrap(),
+        Type::tuple(vec!["A", "B"]),
+    );
+    assert_eq!(
+        Type::parse("fn(u32) -> bool").unwrap(),
+        Type::fn_ptr(vec!["u32"], Type::new("bool")),
+    );
+    assert_eq!(
+        Type::parse("<T as Iterator>::Item").unwrap(),
+        Type::qpath("T", Type::new("Iterator"), "Item"),
+    );
+
+    assert!(Type::parse("Vec<u8").is_err());
+    assert!(Type::parse("T, U").is_err());
+}
+
+#[test]
+fn parsed_type_round_trip() {
+    let srcs = [
+        "std::collections::HashMap<K, Vec<u8>>",
+        "&'static str",
+        "*mut *const c_void",
+        "[&[u8]; N * 2]",
+        "(u8,)",
+        "Box<dyn Fn(&str) -> Result<(), Error> + Send + 'static>",
+        "impl Iterator<Item = (usize, char)>",
+        "&(dyn Any + Send)",
+        "<Self>::Output",
+        "::core::marker::PhantomData<fn() -> !>",
+    ];
+
+    for src in &srcs {
+        let mut scope = Scope::new();
+        scope.new_struct("Foo").field("x", *src);
+
+        let expect = format!("struct Foo {{\n    x: {},\n}}", src);
+        assert_eq!(scope.to_string(), expect);
+    }
+}
+
+#[test]
+fn rewrite_parsed_type() {
+    let mut scope = Scope::new();
+
+    let mut ty = Type::from("Vec<u8>");
+    ty.generic("A");
+
+    scope.new_struct("Foo")
+        .field("one", ty)
+        .field("two", Type::from("collections::HashMap<K, V>").path("std"));
+
+    let expect = r#"
+struct Foo {
+    one: Vec<u8, A>,
+    two: std::collections::HashMap<K, V>,
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn struct_with_generic_params() {
+    let mut scope = Scope::new();
+
+    let mut t = GenericParam::new("T");
+    t.bound("Clone").default("String");
+
+    let s = scope.new_struct("Foo")
+        .generic(t)
+        .generic("const N: usize")
+        .generic("'a")
+        .generic("U: ?Sized")
+        .field("one", "&'a [T; N]")
+        .field("two", "&'a U");
+
+    let mut ty = String::new();
+    s.ty().fmt(&mut Formatter::new(&mut ty)).unwrap();
+    assert_eq!(ty, "Foo<'a, N, U, T>");
+
+    let expect = r#"
+struct Foo<'a, const N: usize, U: ?Sized, T: Clone = String> {
+    one: &'a [T; N],
+    two: &'a U,
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn verbatim_generic_params() {
+    let mut scope = Scope::new();
+
+    let s = scope.new_struct("Foo")
+        .generic("F: for<'b> Fn(&'b u8)")
+        .generic("'a")
+        .field("f", "&'a F");
+
+    let mut ty = String::new();
+    s.ty().fmt(&mut Formatter::new(&mut ty)).unwrap();
+    assert_eq!(ty, "Foo<'a, F>");
+
+    let expect = r#"
+struct Foo<'a, F: for<'b> Fn(&'b u8)> {
+    f: &'a F,
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+    assert_eq!(GenericParam::from("F: for<'b> Fn(&'b u8)").name(), "F");
+}
+
+#[test]
+fn fn_and_impl_with_generic_params() {
+    let mut scope = Scope::new();
+
+    let mut b = GenericParam::new_lifetime("b");
+    b.bound(Type::lifetime("a"));
+
+    let imp = scope.new_impl("Foo<'a, T, N>")
+        .generic("'a")
+        .generic(GenericParam::new_const("N", "usize"))
+        .generic("T: Clone");
+
+    imp.new_fn("get")
+        .generic(b)
+        .generic("I: Iterator<Item = &'b T>")
+        .arg("iter", "I")
+        .line("todo!()");
+
+    let expect = r#"
+impl<'a, const N: usize, T: Clone> Foo<'a, T, N> {
+    fn get<'b: 'a, I: Iterator<Item = &'b T>>(iter: I) {
+        todo!()
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn fn_with_where_predicates() {
+    let mut scope = Scope::new();
+
+    let mut de = WherePredicate::new("T");
+    de.for_lifetime("de").bound("Deserialize<'de>");
+
+    let f = scope.new_fn("load")
+        .generic("'a")
+        .generic("'b")
+        .generic("T")
+        .bound("T", "Clone")
+        .bound("T", "Send")
+        .bound("T", "Clone")
+        .push_predicate(de)
+        .bound("'a", "'b")
+        .ret("T")
+        .line("todo!()");
+
+    f.new_predicate("Self").bound("Sized");
+    f.new_predicate("T").bound("'static");
+
+    let expect = r#"
+fn load<'a, 'b, T>() -> T
+where T: Clone + Send + 'static,
+      for<'de> T: Deserialize<'de>,
+      'a: 'b,
+      Self: Sized,
+{
+    todo!()
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn impl_with_merged_where_predicates() {
+    let mut scope = Scope::new();
+
+    let mut pred = WherePredicate::new("T");
+    pred.bound("Debug").bound("Display");
+
+    scope.new_impl("Foo<T>")
+        .generic("T")
+        .bound("T", "Debug")
+        .push_predicate(pred)
+        .new_fn("show")
+        .line("todo!()");
+
+    let expect = r#"
+impl<T> Foo<T>
+where T: Debug + Display,
+{
+    fn show() {
+        todo!()
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn fn_with_stmts() {
+    let mut scope = Scope::new();
+
+    let f = scope.new_fn("sum")
+        .arg("items", "&[u32]")
+        .ret("Result<u32, Error>");
+
+    f.new_let("total")
+        .set_mut(true)
+        .ty("u32")
+        .init(Expr::lit(0));
+
+    let mut add = Closure::new(Expr::binary(
+        Expr::binary("acc", BinOp::Add, "x"),
+        BinOp::Mul,
+        Expr::lit(2),
+    ));
+    add.param("acc").param("x");
+
+    f.push_stmt(Stmt::semi(Expr::binary(
+        "total",
+        BinOp::AddAssign,
+        Expr::path("items")
+            .method("iter", Vec::<Expr>::new())
+            .method("fold", vec![Expr::lit(0), add.into()]),
+    )));
+
+    f.push_stmt(Stmt::semi(Expr::call(
+        "check",
+        vec![Expr::unary(UnOp::RefMut, "total"), Expr::str("a \"quoted\" name")],
+    ).r#try()));
+
+    f.push_stmt(Stmt::semi(Expr::ret(Expr::call(
+        "Ok",
+        vec![Expr::binary("total", BinOp::Sub, "1").cast("u32")],
+    ))));
+
+    let expect = r#"
+fn sum(items: &[u32]) -> Result<u32, Error> {
+    let mut total: u32 = 0;
+    total += items.iter().fold(0, |acc, x| (acc + x) * 2);
+    check(&mut total, "a \"quoted\" name")?;
+    return Ok((total - 1) as u32);
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn expr_precedence() {
+    let fmt = |expr: Expr| {
+        let mut dst = String::new();
+        expr.fmt(&mut Formatter::new(&mut dst)).unwrap();
+        dst
+    };
+
+    assert_eq!(fmt(Expr::binary("a", BinOp::Sub, Expr::binary("b", BinOp::Sub, "c"))), "a - (b - c)");
+    assert_eq!(fmt(Expr::binary(Expr::binary("a", BinOp::Sub, "b"), BinOp::Sub, "c")), "a - b - c");
+    assert_eq!(fmt(Expr::binary(Expr::binary("a", BinOp::Lt, "b"), BinOp::Eq, "c")), "(a < b) == c");
+    assert_eq!(fmt(Expr::binary("a", BinOp::Assign, Expr::binary("b", BinOp::Assign, "c"))), "a = b = c");
+    assert_eq!(fmt(Expr::unary(UnOp::Not, Expr::path("a").method("is_empty", Vec::<Expr>::new()))), "!a.is_empty()");
+    assert_eq!(fmt(Expr::unary(UnOp::Deref, "x").field("y")), "(*x).y");
+    assert_eq!(fmt(Expr::raw("a + b").method("max", vec!["c"])), "(a + b).max(c)");
+    assert_eq!(fmt(Expr::raw("foo(a, b)").r#try()), "foo(a, b)?");
+    assert_eq!(fmt(Expr::lit(-1).method("abs", Vec::<Expr>::new())), "(-1).abs()");
+    assert_eq!(fmt(Expr::tuple(vec![Expr::lit(1)])), "(1,)");
+    assert_eq!(fmt(Expr::range("a", Expr::binary("b", BinOp::Add, "1"))), "a..b + 1");
+
+    // A cast followed by `<` or `<<` would start generic arguments
+    assert_eq!(fmt(Expr::binary(Expr::path("a").cast("u32"), BinOp::Lt, "b")), "(a as u32) < b");
+    assert_eq!(fmt(Expr::binary(Expr::path("a").cast("u32"), BinOp::Shl, "2")), "(a as u32) << 2");
+    assert_eq!(fmt(Expr::binary(Expr::binary("x", BinOp::Add, Expr::path("a").cast("u32")), BinOp::Lt, "b")), "(x + a as u32) < b");
+    assert_eq!(fmt(Expr::binary(Expr::path("a").cast("u32"), BinOp::Gt, "b")), "a as u32 > b");
+    assert_eq!(fmt(Expr::binary("b", BinOp::Lt, Expr::path("a").cast("u32"))), "b < a as u32");
+}
+
+#[test]
+fn closure_with_block_body() {
+    let mut scope = Scope::new();
+
+    let mut body = Block::default();
+    body.new_let("y").init(Expr::binary("x", BinOp::Add, "1"));
+    body.push_expr("y");
+
+    let mut closure = Closure::new(body);
+    closure.set_move(true).typed_param("x", "u32").ret("u32");
+
+    scope.new_fn("make")
+        .new_let("f")
+        .init(closure);
+
+    let expect = r#"
+fn make() {
+    let f = move |x: u32| -> u32 {
+        let y = x + 1;
+        y
+    };
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn fn_with_match() {
+    let mut scope = Scope::new();
+
+    let mut m = Match::new(Expr::path("token").method("kind", Vec::<Expr>::new()));
+
+    m.arm(Pat::or(vec![Pat::path("Kind::Plus"), Pat::path("Kind::Minus")]), Expr::lit(1));
+    m.new_arm(Pat::tuple_struct("Kind::Num", vec![Pat::ident("n")]))
+        .guard(Expr::binary("n", BinOp::Gt, Expr::lit(0)))
+        .push_expr(Expr::path("n"));
+    m.arm(Pat::r#struct("Kind::Pos").field("line", Pat::ident("line")).field("col", Pat::lit(0)).rest(), "line");
+    m.arm(Pat::range("'a'", "'z'").bind("c"), Expr::call("ord", vec!["c"]));
+    m.arm(Pat::str("nil"), Expr::lit(0));
+    m.new_arm(Pat::Wild)
+        .new_let("msg")
+        .init(Expr::call("format!", vec![Expr::str("unexpected {:?}"), Expr::path("token")]));
+    m.new_arm(Pat::Wild).line("panic!(msg)");
+
+    scope.new_fn("score")
+        .arg("token", "Token")
+        .ret("i32")
+        .push_expr(m);
+
+    let expect = r#"
+fn score(token: Token) -> i32 {
+    match token.kind() {
+        Kind::Plus | Kind::Minus => 1,
+        Kind::Num(n) if n > 0 => n,
+        Kind::Pos { line, col: 0, .. } => line,
+        c @ 'a'..='z' => ord(c),
+        "nil" => 0,
+        _ => {
+            let msg = format!("unexpected {:?}", token);
+        }
+        _ => {
+            panic!(msg)
+        }
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn nested_match_in_block() {
+    let mut scope = Scope::new();
+
+    let mut inner = Match::new("b");
+    inner.arm("true", "1").arm("false", "2");
+
+    let mut outer = Match::new("a");
+    outer.new_arm("Some(b)").push_expr(inner);
+    outer.new_arm("None");
+
+    let mut block = Block::new("unsafe");
+    block.new_let("x").init(outer);
+
+    scope.new_fn("f").push_block(block);
+
+    let expect = r#"
+fn f() {
+    unsafe {
+        let x = match a {
+            Some(b) => match b {
+                true => 1,
+                false => 2,
+            }
+            None => {}
+        };
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn fn_with_if_chain() {
+    let mut scope = Scope::new();
+
+    let mut cond = If::new(Expr::binary("n", BinOp::Lt, Expr::lit(0)));
+    cond.then().push_expr(Expr::str("negative"));
+    cond.else_if_let(Pat::tuple_struct("Some", vec!["name"]), Expr::call("lookup", vec!["n"]))
+        .push_expr("name");
+    cond.r#else().push_expr(Expr::str("other"));
+
+    scope.new_fn("describe")
+        .arg("n", "i32")
+        .ret("&'static str")
+        .push_expr(cond);
+
+    let expect = r#"
+fn describe(n: i32) -> &'static str {
+    if n < 0 {
+        "negative"
+    } else if let Some(name) = lookup(n) {
+        name
+    } else {
+        "other"
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn fn_with_labeled_loops() {
+    let mut scope = Scope::new();
+
+    let mut skip = If::new("x == 0");
+    skip.then().push_stmt(Stmt::semi(Expr::r#continue().label("outer")));
+
+    let mut inner = While::new_let(Pat::tuple_struct("Some", vec!["x"]), Expr::path("it").method("next", Vec::<Expr>::new()));
+    inner.body()
+        .push_expr(skip)
+        .push_stmt(Stmt::semi(Expr::r#break().label("outer")));
+
+    let mut outer = For::new("row", "rows");
+    outer.label("outer");
+    outer.body().push_expr(inner);
+
+    let mut result = Loop::new();
+    result.body().push_stmt(Stmt::semi(Expr::break_with(Expr::lit(1))));
+
+    let mut block = Block::default();
+    block.label("done").push_stmt(Stmt::semi(Expr::r#break().label("done")));
+
+    scope.new_fn("scan")
+        .push_expr(outer)
+        .push_expr(block)
+        .push_stmt(Stmt::semi(result));
+
+    let expect = r#"
+fn scan() {
+    'outer: for row in rows {
+        while let Some(x) = it.next() {
+            if x == 0 {
+                continue 'outer;
+            }
+            break 'outer;
+        }
+    }
+    'done: {
+        break 'done;
+    }
+    loop {
+        break 1;
+    };
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn type_aliases() {
+    let mut scope = Scope::new();
+
+    scope.new_type_alias("Result", "std::result::Result<T, Error>")
+        .vis("pub")
+        .doc("Result type of this crate.")
+        .generic("T");
+
+    scope.new_module("io")
+        .new_type_alias("Handler", "Box<dyn Fn(&'a mut T) + Send>")
+        .generic("'a")
+        .generic("T")
+        .bound("T", "Read")
+        .bound("T", "'a")
+        .r#macro("#[cfg(unix)]");
+
+    let expect = r#"
+/// Result type of this crate.
+pub type Result<T> = std::result::Result<T, Error>;
+
+mod io {
+    #[cfg(unix)]
+    type Handler<'a, T> = Box<dyn Fn(&'a mut T) + Send>
+    where T: Read + 'a;
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn repr_c_union() {
+    let mut scope = Scope::new();
+
+    let mut field = Field::new("f", "f32");
+    field.doc(vec!["Float view."]);
+
+    scope.new_union("Bits")
+        .vis("pub")
+        .doc("Raw view of a 32-bit value.")
+        .derive("Clone")
+        .derive("Copy")
+        .repr("C")
+        .field_pub("i", "u32")
+        .push_field(field);
+
+    scope.new_module("ffi")
+        .new_union("Slot")
+        .generic("T: Copy")
+        .field("value", "T")
+        .field("next", "*mut Slot<T>");
+
+    let expect = r#"
+/// Raw view of a 32-bit value.
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub union Bits {
+    pub i: u32,
+    /// Float view.
+    f: f32,
+}
+
+mod ffi {
+    union Slot<T: Copy> {
+        value: T,
+        next: *mut Slot<T>,
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn extern_block_with_foreign_items() {
+    let mut scope = Scope::new();
+
+    {
+        let block = scope.new_extern_block("C");
+        block.link("c").doc("libc bindings.");
+        block.new_fn("abs").arg("x", "i32").ret("i32");
+        block.new_fn("printf")
+            .vis("pub")
+            .arg("fmt", "*const c_char")
+            .set_variadic(true)
+            .ret("c_int");
+        block.new_static("errno", "c_int").vis("pub");
+    }
+
+    scope.new_module("sys")
+        .new_extern_block("system")
+        .set_unsafe(true)
+        .new_fn("GetLastError")
+        .set_unsafe(true)
+        .ret("u32");
+
+    let expect = r#"
+/// libc bindings.
+#[link(name = "c")]
+extern "C" {
+    fn abs(x: i32) -> i32;
+    pub fn printf(fmt: *const c_char, ...) -> c_int;
+    pub static errno: c_int;
+}
+
+mod sys {
+    unsafe extern "system" {
+        unsafe fn GetLastError() -> u32;
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+#[should_panic(expected = "foreign fns can only be unsafe in an `unsafe extern` block")]
+fn unsafe_foreign_fn_in_safe_block() {
+    let mut scope = Scope::new();
+
+    scope.new_extern_block("C")
+        .new_fn("g")
+        .set_unsafe(true);
+
+    scope.to_string();
+}
+
+#[test]
+#[should_panic(expected = "foreign fns cannot have type or const parameters")]
+fn generic_foreign_fn() {
+    let mut scope = Scope::new();
+
+    scope.new_extern_block("C")
+        .new_fn("g")
+        .generic("T")
+        .arg("x", "T");
+
+    scope.to_string();
+}
+
+#[test]
+fn macro_rules_definition() {
+    let mut scope = Scope::new();
+
+    {
+        let module = scope.new_module("macros");
+        module.macro_use();
+        module.new_macro_rules("hash_map")
+            .doc("Builds a `HashMap` from key-value pairs.")
+            .set_export(true)
+            .arm("", "")
+            .arm("$($k:expr => $v:expr),+ $(,)?", r#"
+                {
+                    let mut map = ::std::collections::HashMap::new();
+
+                    $(map.insert($k, $v);)+
+                    map
+                }
+            "#);
+    }
+
+    scope.new_macro_rules("square")
+        .attr("allow(unused_macros)")
+        .arm("$x:expr", "$x * $x");
+
+    let expect = r#"
+#[macro_use]
+mod macros {
+    /// Builds a `HashMap` from key-value pairs.
+    #[macro_export]
+    macro_rules! hash_map {
+        () => {};
+        ($($k:expr => $v:expr),+ $(,)?) => {
+            {
+                let mut map = ::std::collections::HashMap::new();
+
+                $(map.insert($k, $v);)+
+                map
+            }
+        };
+    }
+}
+
+#[allow(unused_macros)]
+macro_rules! square {
+    ($x:expr) => {
+        $x * $x
+    };
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn macro_calls() {
+    let mut scope = Scope::new();
+
+    scope.new_macro_call("include!").tokens(r#"concat!(env!("OUT_DIR"), "/gen.rs")"#);
+
+    scope.new_module("flags")
+        .new_macro_call("bitflags")
+        .delimiter(Delimiter::Brace)
+        .doc("Permission bits.")
+        .scope()
+        .new_struct("Perms: u32")
+        .derive("Default");
+
+    scope.new_macro_call("lazy_static")
+        .delimiter(Delimiter::Brace)
+        .attr("cfg(feature = \"std\")")
+        .tokens("
+            static ref NAMES: Vec<&'static str> = {
+                vec![\"a\", \"b\"]
+            };
+        ");
+
+    let mut vec = MacroCall::new("vec");
+    vec.delimiter(Delimiter::Bracket).tokens("1, 2, 3");
+
+    let mut print = MacroCall::new("println");
+    print.tokens("\"{:?}\", v");
+
+    let main = scope.new_fn("main");
+    main.new_let("v").init(vec);
+    main.push_stmt(print);
+
+    let expect = r#"
+include!(concat!(env!("OUT_DIR"), "/gen.rs"));
+
+mod flags {
+    /// Permission bits.
+    bitflags! {
+        #[derive(Default)]
+        struct Perms: u32;
+    }
+}
+
+#[cfg(feature = "std")]
+lazy_static! {
+    static ref NAMES: Vec<&'static str> = {
+        vec!["a", "b"]
+    };
+}
+
+fn main() {
+    let v = vec![1, 2, 3];
+    println!("{:?}", v);
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn macro_call_tokens_with_mixed_indentation() {
+    let mut scope = Scope::new();
+
+    scope.new_macro_call("tabs")
+        .delimiter(Delimiter::Brace)
+        .tokens("\n\t\tlet a = 1;\n\t  let b = 2;\n");
+
+    scope.new_macro_call("wide")
+        .delimiter(Delimiter::Brace)
+        .tokens("\n\u{3000}let a = 1;\n  let b = 2;\n");
+
+    let expect = "\
+tabs! {
+    \tlet a = 1;
+      let b = 2;
+}
+
+wide! {
+    \u{3000}let a = 1;
+      let b = 2;
+}";
+
+    assert_eq!(scope.to_string(), expect);
+}
+
+#[test]
+fn macro_call_items_use_enclosing_imports() {
+    let mut scope = Scope::new();
+    scope.auto_import(true);
+
+    scope.new_macro_call("bitflags::bitflags")
+        .delimiter(Delimiter::Brace)
+        .scope()
+        .new_struct("Flags")
+        .derive("Default")
+        .field("bits", "std::collections::HashMap<u8, u8>");
+
+    scope.new_struct("Table")
+        .field("flags", "std::collections::HashMap<u8, u8>");
+
+    let expect = r#"
+use std::collections::HashMap;
+
+bitflags::bitflags! {
+    #[derive(Default)]
+    struct Flags {
+        bits: HashMap<u8, u8>,
+    }
+}
+
+struct Table {
+    flags: HashMap<u8, u8>,
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn use_trees() {
+    let mut scope = Scope::new();
+
+    scope.extern_crate("alloc");
+    scope.extern_crate("serde_json").alias("json").vis("pub");
+
+    scope.import("bar", "Bar");
+    scope.import_tree("foo::{self, bar::{X, Y}}");
+    scope.import_tree("foo::baz::*");
+    scope.import_tree("std::fmt::Result as FmtResult");
+    scope.import_tree("serde");
+    scope.import_tree(UseTree::path("std::collections", UseTree::name("HashMap")));
+    scope.import_tree("foo::bar::X");
+
+    let mut import = Import::tree("crate::{model::*, error::Error}");
+    import.vis("pub(crate)");
+    scope.push_import(import);
+
+    scope.new_module("inner")
+        .import_tree("super::*")
+        .extern_crate("core");
+
+    let expect = r#"
+extern crate alloc;
+pub extern crate serde_json as json;
+use bar::Bar;
+use foo::{self, bar::{X, Y}, baz::*};
+use std::{fmt::Result as FmtResult, collections::HashMap};
+use serde;
+pub(crate) use crate::{model::*, error::Error};
+
+mod inner {
+    extern crate core;
+    use super::*;
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn import_groups_and_globs() {
+    let mut scope = Scope::new();
+
+    scope.import("foo", "{A, B}");
+    scope.import("std::collections", "HashMap");
+    scope.import_tree("std::fmt");
+    scope.import("std::io", "Read");
+    scope.import("std::collections", "HashMap");
+
+    scope.new_module("inner")
+        .import_tree("*");
+
+    let expect = r#"
+use foo::{A, B};
+use std::{collections::HashMap, fmt, io::Read};
+
+mod inner {
+    use *;
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn parse_use_tree() {
+    assert_eq!(
+        UseTree::parse("::foo::{self, Bar as _,}").unwrap(),
+        UseTree::path("::foo", UseTree::group(vec![
+            UseTree::name("self"),
+            UseTree::rename("Bar", "_"),
+        ])),
+    );
+
+    assert!(UseTree::parse("foo::{Bar").is_err());
+    assert!(UseTree::parse("foo as").is_err());
+}
+
+#[test]
+fn auto_import_qualified_types() {
+    let mut scope = Scope::new();
+    scope.auto_import(true);
+    scope.import("std::sync", "Arc");
+
+    scope.new_struct("Cache")
+        .field("map", "std::collections::HashMap<String, std::sync::Arc<Entry>>")
+        .field("res", "std::fmt::Result")
+        .field("io", "std::io::Result<()>")
+        .field("out", "T::Output")
+        .generic("T");
+
+    scope.new_struct("Error");
+
+    scope.new_fn("load")
+        .ret("std::result::Result<(), crate::error::Error>")
+        .line("todo!()");
+
+    scope.new_module("legacy")
+        .auto_import(false)
+        .new_struct("Old")
+        .field("map", "std::collections::HashMap<u32, u32>");
+
+    scope.new_module("nested")
+        .new_struct("New")
+        .field("set", "std::collections::HashSet<u32>");
+
+    let expect = r#"
+use std::sync::Arc;
+use std::collections::HashMap;
+use std::fmt::Result as FmtResult;
+use std::io::Result as IoResult;
+use crate::error::Error as ErrorError;
+
+struct Cache<T> {
+    map: HashMap<String, Arc<Entry>>,
+    res: FmtResult,
+    io: IoResult<()>,
+    out: T::Output,
+}
+
+struct Error;
+
+fn load() -> Result<(), ErrorError> {
+    todo!()
+}
+
+mod legacy {
+    struct Old {
+        map: std::collections::HashMap<u32, u32>,
+    }
+}
+
+mod nested {
+    use std::collections::HashSet;
+
+    struct New {
+        set: HashSet<u32>,
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn auto_import_avoids_prelude_and_unqualified_names() {
+    let mut scope = Scope::new();
+    scope.auto_import(true);
+
+    scope.new_fn("read")
+        .ret("std::io::Result<()>")
+        .line("Ok(())");
+
+    scope.new_fn("parse")
+        .ret("Result<u8, String>")
+        .line("Ok(0)");
+
+    scope.new_fn("check")
+        .ret("Checked")
+        .line("Checked");
+
+    scope.new_fn("wrap")
+        .ret("other::Checked")
+        .line("todo!()");
+
+    let expect = r#"
+use std::io::Result as IoResult;
+use other::Checked as OtherChecked;
+
+fn read() -> IoResult<()> {
+    Ok(())
+}
+
+fn parse() -> Result<u8, String> {
+    Ok(0)
+}
+
+fn check() -> Checked {
+    Checked
+}
+
+fn wrap() -> OtherChecked {
+    todo!()
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn auto_import_avoids_generic_params() {
+    let mut scope = Scope::new();
+    scope.auto_import(true);
+
+    scope.new_struct("Pair")
+        .generic("T")
+        .field("a", "T")
+        .field("b", "other::T");
+
+    let expect = r#"
+use other::T as OtherT;
+
+struct Pair<T> {
+    a: T,
+    b: OtherT,
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn auto_import_deeply_nested_modules() {
+    let mut scope = Scope::new();
+    scope.auto_import(true);
+    scope.prune_imports(PruneImports::Types);
+
+    let mut module = scope.new_module("m0");
+
+    for depth in 1..32 {
+        module.new_struct("Id").field("0", "std::num::NonZeroU32");
+        module = module.new_module(format!("m{}", depth));
+    }
+
+    module.new_struct("Id").field("0", "std::num::NonZeroU64");
+
+    let out = scope.to_string();
+    assert_eq!(out.matches("use std::num::NonZeroU32;").count(), 31);
+    assert!(out.contains("use std::num::NonZeroU64;"));
+}
+
+#[test]
+fn grouped_and_sorted_imports() {
+    let mut scope = Scope::new();
+    scope.import_order(ImportOrder::Grouped);
+    scope.import("super", "Parent");
+    scope.import_tree("serde::{Serialize, Deserialize}");
+    scope.import("std::fmt", "Write");
+    scope.import_tree("crate::model::{self, Id}");
+    scope.import("std::fmt", "Display");
+    scope.import_tree("std::fmt::Write");
+    scope.import_tree("anyhow::Result");
+    scope.import("core::mem", "swap");
+    scope.import("crate::model", "Id");
+    scope.extern_crate("log");
+    scope.extern_crate("alloc");
+
+    scope.new_module("sorted")
+        .import_order(ImportOrder::Sorted)
+        .import("super", "Parent")
+        .import("std::io", "Read")
+        .import("bytes", "Bytes");
+
+    let expect = r#"
+extern crate alloc;
+extern crate log;
+use core::mem::swap;
+use std::fmt::{Display, Write};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::model::{self, Id};
+use super::Parent;
+
+mod sorted {
+    use bytes::Bytes;
+    use std::io::Read;
+    use super::Parent;
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn prune_unused_imports() {
+    let mut scope = Scope::new();
+    scope.prune_imports(PruneImports::Types);
+    scope.import("std::collections", "HashMap");
+    scope.import("std::collections", "BTreeMap");
+    scope.import("std::rc", "Rc");
+    scope.import_tree("std::fmt");
+    scope.import_tree("std::io::Read as _");
+    scope.import("crate::prelude", "*");
+    scope.import("crate::model", "Id").vis("pub");
+    scope.import("crate::model", "Kind");
+
+    scope.new_struct("Index")
+        .field("map", "HashMap<String, u32>");
+
+    scope.new_impl("Index")
+        .impl_trait("fmt::Debug")
+        .new_fn("fmt")
+        .arg_ref_self()
+        .arg("f", "&mut fmt::Formatter<'_>")
+        .ret("fmt::Result")
+        .line("let sorted = BTreeMap::new();")
+        .line("todo!()");
+
+    scope.new_fn("is_leaf")
+        .arg("kind", "u8")
+        .ret("bool")
+        .push_expr(Expr::call(Expr::path("matches!"), vec![
+            Expr::path("kind"),
+            Expr::path("Kind::LEAF"),
+        ]));
+
+    scope.new_module("nested")
+        .prune_imports(PruneImports::TypesAndTokens)
+        .import("std::collections", "BTreeMap")
+        .import("std::rc", "Rc")
+        .new_fn("sorted")
+        .line("BTreeMap::<u32, u32>::new()");
+
+    let expect = r#"
+use std::{collections::HashMap, fmt, io::Read as _};
+use crate::prelude::*;
+use crate::model::Kind;
+pub use crate::model::Id;
+
+struct Index {
+    map: HashMap<String, u32>,
+}
+
+impl fmt::Debug for Index {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sorted = BTreeMap::new();
+        todo!()
+    }
+}
+
+fn is_leaf(kind: u8) -> bool {
+    matches!(kind, Kind::LEAF)
+}
+
+mod nested {
+    use std::collections::BTreeMap;
+
+    fn sorted() {
+        BTreeMap::<u32, u32>::new()
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn prune_keeps_derives_macros_and_kept_imports() {
+    let mut scope = Scope::new();
+    scope.prune_imports(PruneImports::Types);
+    scope.import("serde", "Serialize");
+    scope.import("serde", "Deserialize");
+    scope.import("lazy_static", "lazy_static");
+    scope.import("tracing", "instrument");
+    scope.import("std::io", "Write").keep(true);
+    scope.import("std::io", "Read");
+
+    scope.new_struct("Config")
+        .derive("Serialize")
+        .cfg_attr(Cfg::feature("de"), Attr::parse("derive(Deserialize)").unwrap())
+        .field("path", "String");
+
+    scope.new_macro_call("lazy_static")
+        .delimiter(Delimiter::Brace)
+        .tokens("
+            static ref CONFIG: Config = load();
+            static ref PATH: String = CONFIG.path.clone();
+        ");
+
+    scope.new_fn("save")
+        .attr("instrument")
+        .arg("out", "&mut Vec<u8>")
+        .line("out.write_all(b\"{}\").unwrap();");
+
+    let expect = r#"
+use serde::{Serialize, Deserialize};
+use lazy_static::lazy_static;
+use tracing::instrument;
+use std::io::Write;
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "de", derive(Deserialize))]
+struct Config {
+    path: String,
+}
+
+lazy_static! {
+    static ref CONFIG: Config = load();
+    static ref PATH: String = CONFIG.path.clone();
+}
+
+#[instrument]
+fn save(out: &mut Vec<u8>) {
+    out.write_all(b"{}").unwrap();
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn structured_visibility() {
+    let mut scope = Scope::new();
+
+    scope.new_struct("Public").vis(Visibility::Pub);
+    scope.new_struct("Local").vis("pub (crate)");
+    scope.new_fn("helper").vis(Visibility::In("crate::model".into()));
+    scope.new_fn("run").vis("pub");
+    scope.new_module("inner").vis(Visibility::Super);
+    scope.new_enum("Hidden").vis("pub(self)");
+    scope.new_struct("Legacy").vis(Visibility::Verbatim("crate".into()));
+
+    assert_eq!(scope.public_names(), vec!["Public", "run"]);
+    assert_eq!(Visibility::parse("pub(in crate::a)"), Ok(Visibility::In("crate::a".into())));
+    assert!(Visibility::parse("pub(crate").is_err());
+    assert!(Visibility::parse("crate").is_err());
+    assert!(!Visibility::Verbatim("$vis".into()).is_private());
+
+    let expect = r#"
+pub struct Public;
+
+pub(crate) struct Local;
+
+pub(in crate::model) fn helper() {
+}
+
+pub fn run() {
+}
+
+pub(super) mod inner {
+}
+
+pub(self) enum Hidden {
+}
+
+crate struct Legacy;"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+#[should_panic(expected = "invalid visibility `pub(crat)`")]
+fn invalid_visibility_string() {
+    let mut scope = Scope::new();
+    scope.new_struct("A").vis("pub(crat)");
+}
+
+#[test]
+fn var_def_vis_docs_and_attrs() {
+    let mut scope = Scope::new();
+
+    scope.new_const("MAX", "usize")
+        .vis("pub")
+        .doc("Maximum number of entries.")
+        .value("64");
+
+    scope.new_static_mut("COUNTER", "u32")
+        .vis(Visibility::Crate)
+        .value("0");
+
+    scope.new_static("HOOK", "extern \"C\" fn()")
+        .value("hook")
+        .new_attr("used");
+
+    scope.push_var_def(VarDef::new_unnamed_const("assert!(MAX > 0)"));
+
+    let mut id = VarDef::new_const("ID", "u32");
+    id.doc("Unique id.");
+
+    let mut name = VarDef::new_const("NAME", "&'static str");
+    name.value("\"node\"");
+
+    scope.new_trait("Node")
+        .push_const(id)
+        .push_const(name);
+
+    let mut id = VarDef::new_const("ID", "u32");
+    id.value("1");
+
+    scope.new_impl("Leaf")
+        .impl_trait("Node")
+        .associate_type("Output", "()")
+        .push_const(id)
+        .new_fn("run")
+        .arg_ref_self();
+
+    let expect = r#"
+/// Maximum number of entries.
+pub const MAX: usize = 64;
+
+pub(crate) static mut COUNTER: u32 = 0;
+
+#[used]
+static HOOK: extern "C" fn() = hook;
+
+const _: () = assert!(MAX > 0);
+
+trait Node {
+    /// Unique id.
+    const ID: u32;
+    const NAME: &'static str = "node";
+}
+
+impl Node for Leaf {
+    type Output = ();
+
+    const ID: u32 = 1;
+
+    fn run(&self) {
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn associated_consts() {
+    let mut scope = Scope::new();
+
+    let shape = scope.new_trait("Shape");
+    shape.new_const("SIDES", "usize");
+    shape.associated_type("Unit");
+    shape.new_const("MAX", "usize").value("10");
+
+    let imp = scope.new_impl("Square");
+    imp.impl_trait("Shape");
+    imp.new_fn("area").arg_ref_self().ret("f64").line("self.0 * self.0");
+    imp.new_const("SIDES", "usize", "4");
+    imp.associate_type("Unit", "f64");
+
+    scope.new_impl("Square")
+        .new_const("UNIT", "Square", "Square(1.0)")
+        .vis("pub");
+
+    let expect = r#"
+trait Shape {
+    type Unit;
+
+    const SIDES: usize;
+    const MAX: usize = 10;
+}
+
+impl Shape for Square {
+    type Unit = f64;
+
+    const SIDES: usize = 4;
+
+    fn area(&self) -> f64 {
+        self.0 * self.0
+    }
+}
+
+impl Square {
+    pub const UNIT: Square = Square(1.0);
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn required_and_provided_trait_fns() {
+    let mut scope = Scope::new();
+    let visitor = scope.new_trait("Visitor");
+
+    visitor.new_fn("visit_node")
+        .arg_mut_self()
+        .arg("node", "&Node");
+
+    visitor.new_provided_fn("visit_leaf")
+        .arg_mut_self()
+        .arg("leaf", "&Leaf")
+        .line("let _ = leaf;");
+
+    visitor.new_provided_fn("finish")
+        .arg_mut_self();
+
+    visitor.new_fn("reset")
+        .arg_mut_self();
+
+    assert_eq!(visitor.required_fns(), vec!["visit_node", "reset"]);
+    assert_eq!(visitor.provided_fns(), vec!["visit_leaf", "finish"]);
+
+    let expect = r#"
+trait Visitor {
+    fn visit_node(&mut self, node: &Node);
+
+    fn visit_leaf(&mut self, leaf: &Leaf) {
+        let _ = leaf;
+    }
+
+    fn finish(&mut self) {
+    }
+
+    fn reset(&mut self);
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn fn_qualifiers() {
+    let mut scope = Scope::new();
+
+    scope.new_fn("len")
+        .vis("pub")
+        .set_const(true)
+        .ret("usize")
+        .line("0");
+
+    scope.new_fn("from_raw")
+        .set_const(true)
+        .set_unsafe(true)
+        .arg("ptr", "*const u8")
+        .ret("u8")
+        .line("*ptr");
+
+    scope.new_fn("callback")
+        .set_unsafe(true)
+        .extern_abi("C")
+        .arg("data", "*mut c_void");
+
+    scope.new_fn("poll")
+        .set_async(true)
+        .set_unsafe(true);
+
+    scope.new_trait("Raw")
+        .new_fn("read")
+        .set_unsafe(true)
+        .arg_ref_self()
+        .ret("u8");
+
+    let expect = r#"
+pub const fn len() -> usize {
+    0
+}
+
+const unsafe fn from_raw(ptr: *const u8) -> u8 {
+    *ptr
+}
+
+unsafe extern "C" fn callback(data: *mut c_void) {
+}
+
+async unsafe fn poll() {
+}
+
+trait Raw {
+    unsafe fn read(&self) -> u8;
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+#[should_panic(expected = "const fns cannot be async")]
+fn const_async_fn() {
+    let mut scope = Scope::new();
+
+    scope.new_fn("run")
+        .set_const(true)
+        .set_async(true);
+
+    scope.to_string();
+}
+
+#[test]
+fn unsafe_negative_and_attributed_impls() {
+    let mut scope = Scope::new();
+
+    scope.new_impl("Handle")
+        .impl_trait("Send")
+        .doc("The handle is only accessed behind a lock.")
+        .set_unsafe(true);
+
+    scope.new_impl("Handle")
+        .impl_trait("Sync")
+        .negative(true);
+
+    let imp = scope.new_impl("Handle");
+    imp.impl_trait("Serialize");
+    imp.new_attr("cfg").arg_delimited("feature = \"serde\"");
+    imp.new_fn("serialize").arg_ref_self().line("todo!()");
+
+    scope.new_impl("Wrapper<T>")
+        .generic("T")
+        .impl_trait("Default")
+        .set_const(true)
+        .bound("T", "Default")
+        .new_fn("default")
+        .ret("Self")
+        .line("Wrapper(T::default())");
+
+    scope.new_impl("Marker<T>")
+        .generic("T")
+        .impl_trait("Copy")
+        .bound("T", "Copy");
+
+    let expect = r#"
+/// The handle is only accessed behind a lock.
+unsafe impl Send for Handle {
+}
+
+impl !Sync for Handle {}
+
+#[cfg(feature = "serde")]
+impl Serialize for Handle {
+    fn serialize(&self) {
+        todo!()
+    }
+}
+
+impl<T> const Default for Wrapper<T>
+where T: Default,
+{
+    fn default() -> Self {
+        Wrapper(T::default())
+    }
+}
+
+impl<T> Copy for Marker<T>
+where T: Copy,
+{
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn attributes_on_every_item() {
+    let mut scope = Scope::new();
+
+    let mut field = Field::new("id", "u64");
+    field.new_attr("serde").arg_delimited("rename = \"ID\"");
+
+    scope.new_struct("Record")
+        .derive("Debug")
+        .push_attr(Attr::from("#[cfg_attr(test, derive(Default))]"))
+        .derive("Clone")
+        .repr("C")
+        .push_field(field);
+
+    let mut variant = Variant::new("Legacy");
+    variant.push_attr(Attr::parse("#[deprecated(note = \"use V2\")]").unwrap());
+
+    scope.new_enum("Version")
+        .push_attr(Attr::parse("non_exhaustive").unwrap())
+        .push_variant(variant)
+        .push_variant(Variant::new("V2"));
+
+    scope.new_fn("open")
+        .attr("inline")
+        .allow("unused_variables")
+        .arg("path", "&str")
+        .arg("fd", "RawFd")
+        .arg_attr(Attr::parse("cfg(unix)").unwrap())
+        .push_stmt(Stmt::from(Let::new("x").set_mut(true).init(Expr::lit("1")).clone())
+            .attr(Attr::parse("allow(unused_mut)").unwrap()));
+
+    let attr = Attr::parse("#[doc = \"text\"]").unwrap();
+    assert_eq!(attr.name, "doc");
+    assert!(Attr::parse("#[serde(default").is_err());
+
+    let expect = r#"
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(Default))]
+#[repr(C)]
+struct Record {
+    #[serde(rename = "ID")]
+    id: u64,
+}
+
+#[non_exhaustive]
+enum Version {
+    #[deprecated(note = "use V2")]
+    Legacy,
+    V2,
+}
+
+#[inline]
+#[allow(unused_variables)]
+fn open(path: &str, #[cfg(unix)] fd: RawFd) {
+    #[allow(unused_mut)]
+    let mut x = 1;
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn structured_attr_args() {
+    let mut serde = Attr::new("serde");
+    serde.list()
+        .name_value("rename", "say \"hi\"")
+        .path("default");
+
+    assert_eq!(serde.to_string(), r#"#[serde(rename = "say \"hi\"", default)]"#);
+
+    let mut other = Attr::parse("serde(rename = \"id\", skip_serializing_if = \"Option::is_none\")").unwrap();
+    other.list().new_list("alias").lit(1).lit(true);
+    serde.merge(other);
+
+    let mut field = Field::new("id", "Option<u64>");
+    field.push_attr(serde);
+
+    let mut scope = Scope::new();
+
+    scope.new_struct("User")
+        .derive("Debug")
+        .derive("Clone")
+        .derive("Debug")
+        .push_field(field);
+
+    let expect = r#"
+#[derive(Debug, Clone)]
+struct User {
+    #[serde(rename = "id", default, skip_serializing_if = "Option::is_none", alias(1, true))]
+    id: Option<u64>,
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+
+    let cfg = Attr::parse(r#"#[cfg(all(unix, target_pointer_width = "64", not(test)))]"#).unwrap();
+    let all = cfg.meta_list().unwrap().get_list("all").unwrap();
+    assert!(all.contains("unix"));
+    assert_eq!(all.value("target_pointer_width").and_then(Lit::as_str), Some("64"));
+    assert!(all.get_list("not").unwrap().contains("test"));
+
+    let raw = Attr::parse("#[doc(alias = r\"a\\b\")]").unwrap();
+    assert_eq!(raw.to_string(), "#[doc(alias = r\"a\\b\")]");
+    assert_eq!(raw.meta_list().unwrap().value("alias"), Some(&Lit::from("a\\b")));
+
+    let mut compact = Attr::new("foo");
+    compact.arg_delimited("a,b");
+    assert_eq!(compact.to_string(), "#[foo(a,b)]");
+    assert_eq!(compact, Attr::parse("foo(a, b)").unwrap());
+
+    compact.list().path("c");
+    assert_eq!(compact.to_string(), "#[foo(a, b, c)]");
+
+    assert!(Attr::parse("foo(a) b)").is_err());
+    assert!(Attr::parse("foo(a(b)").is_err());
+    assert!(Attr::parse("foo(a]").is_err());
+    assert_eq!(Attr::parse(r#"foo(")", [b])"#).unwrap().to_string(), r#"#[foo(")", [b])]"#);
+
+    let tokens = Attr::parse("#[clippy::cfg(x + 1)]").unwrap();
+    assert_eq!(tokens.arg, Some(AttrArg::Delimited("x + 1".to_string())));
+}
+
+#[test]
+#[should_panic(expected = "invalid attribute `foo(a) b)`")]
+fn invalid_attr_string() {
+    let mut scope = Scope::new();
+    scope.new_fn("a").attr("foo(a) b)");
+}
+
+#[test]
+fn cfg_gated_items() {
+    let mut scope = Scope::new();
+
+    scope.extern_crate("alloc").cfg(Cfg::not(Cfg::feature("std")));
+    scope.import("std::fmt", "Debug");
+    scope.import("serde", "Serialize").cfg(Cfg::feature("serde"));
+    scope.import("serde", "Deserialize").cfg(Cfg::feature("serde"));
+
+    let mut field = Field::new("fd", "RawFd");
+    field.cfg(Cfg::name("unix"));
+
+    scope.new_struct("Handle")
+        .derive("Debug")
+        .cfg_attr(Cfg::feature("serde"), Attr::parse("derive(Serialize, Deserialize)").unwrap())
+        .push_field(field);
+
+    scope.new_enum("Backend")
+        .push_variant(Variant::new("Epoll").cfg(Cfg::target_os("linux")).clone())
+        .push_variant(Variant::new("Kqueue")
+            .cfg(Cfg::any([Cfg::target_os("macos"), Cfg::target_os("freebsd")]))
+            .clone());
+
+    scope.new_impl("Handle")
+        .cfg(Cfg::all([Cfg::feature("std"), Cfg::not(Cfg::name("test"))]))
+        .new_fn("close")
+        .arg_ref_self()
+        .cfg_attr(Cfg::feature("tracing"), Attr::parse("instrument(skip(self))").unwrap())
+        .push_stmt(Stmt::Semi(Expr::call(Expr::path("log"), [Expr::lit("1")]))
+            .cfg(Cfg::feature("log")));
+
+    let expect = r#"
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+use std::fmt::Debug;
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct Handle {
+    #[cfg(unix)]
+    fd: RawFd,
+}
+
+enum Backend {
+    #[cfg(target_os = "linux")]
+    Epoll,
+    #[cfg(any(target_os = "macos", target_os = "freebsd"))]
+    Kqueue,
+}
+
+#[cfg(all(feature = "std", not(test)))]
+impl Handle {
+    #[cfg_attr(feature = "tracing", instrument(skip(self)))]
+    fn close(&self) {
+        #[cfg(feature = "log")]
+        log(1);
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+    assert_eq!(scope.features(), ["log", "serde", "std", "tracing"]);
+
+    let cfg = Cfg::parse(r#"any(unix, feature = "a", not(feature = "b"))"#).unwrap();
+    assert_eq!(cfg.features(), ["a", "b"]);
+    assert_eq!(Attr::cfg(cfg.clone()).cfg_predicate(), Some(cfg));
+    assert!(Cfg::parse("unix, windows").is_err());
+
+    let doc = Attr::parse("doc = include_str!(\"a.md\")").unwrap();
+    let gated = Attr::cfg_attr(Cfg::feature("docs"), doc);
+    assert_eq!(gated.to_string(), r#"#[cfg_attr(feature = "docs", doc = include_str!("a.md"))]"#);
+    assert_eq!(gated.cfg_predicate(), Some(Cfg::feature("docs")));
+
+    let doc = Attr::parse("doc = include_str!(\"a.md\")").unwrap();
+    let gated = Attr::cfg_attr(Cfg::feature("a,b"), doc);
+    assert_eq!(gated.cfg_predicate(), Some(Cfg::feature("a,b")));
+
+    let mut scope = Scope::new();
+    scope.new_struct("Doc")
+        .cfg_attr(Cfg::feature("c,d"), Attr::parse("doc = \"x\"").unwrap());
+
+    let gated = Attr::cfg_attr(Cfg::feature("c,d"), Attr::parse("doc = \"x\"").unwrap());
+    assert_eq!(gated.to_string(), r#"#[cfg_attr(feature = "c,d", doc = "x")]"#);
+    assert!(gated.meta_list().is_some());
+    assert_eq!(scope.features(), ["c,d"]);
+}